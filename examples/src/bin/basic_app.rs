@@ -15,7 +15,7 @@ fn main() {
 	
 	basalt.spawn_app_loop();
 	
-	let background = basalt.interface_ref().new_bin();
+	let background = basalt.interface_ref().new_bin().unwrap();
 	
 	background.style_update(BinStyle {
 		pos_from_t: Some(0.0),
@@ -26,7 +26,7 @@ fn main() {
 		.. BinStyle::default()
 	});
 	
-	let button = basalt.interface_ref().new_bin();
+	let button = basalt.interface_ref().new_bin().unwrap();
 	background.add_child(button.clone());
 	
 	button.style_update(BinStyle {
@@ -1,6 +1,8 @@
 use Basalt;
+use logging;
 use misc::TmpImageViewAccess;
-use std::sync::Arc;
+use error::{BasaltError,AtlasError};
+use std::sync::{Arc,Weak};
 use std::thread;
 use std::time::Instant;
 use std::collections::HashMap;
@@ -29,8 +31,6 @@ use crossbeam::queue::SegQueue;
 use crossbeam::sync::Parker;
 use crossbeam::sync::Unparker;
 
-const PRINT_UPDATE_TIME: bool = false;
-
 #[inline]
 fn srgb_to_linear_d8(v: u8) -> u8 {
 	let mut f = v as f32 / 255.0;
@@ -163,18 +163,21 @@ pub struct Image {
 }
 
 impl Image {
-	pub fn new(ty: ImageType, dims: ImageDims, mut data: ImageData) -> Result<Image, String> {
+	pub fn new(ty: ImageType, dims: ImageDims, mut data: ImageData) -> Result<Image, BasaltError> {
 		let expected_len = dims.w as usize * dims.h as usize * ty.components();
 		
 		if expected_len == 0 {
-			return Err(format!("Image can't be empty"));
+			return Err(AtlasError::EmptyImage.into());
 		}
 		
 		match &mut data {
 			&mut ImageData::D8(ref mut d) => if d.len() > expected_len {
 				d.truncate(expected_len);
 			} else if d.len() < expected_len {
-				return Err(format!("Data length doesn't match the provided dimensions."));
+				return Err(AtlasError::DataLength {
+					expected: expected_len,
+					actual: d.len(),
+				}.into());
 			},
 			_ => unreachable!()
 		}
@@ -184,6 +187,22 @@ impl Image {
 		})
 	}
 	
+	pub fn dims(&self) -> ImageDims {
+		self.dims
+	}
+	
+	pub fn ty(&self) -> ImageType {
+		self.ty
+	}
+	
+	/// Convert to ``ImageType::LRGBA`` and take the data.
+	pub(crate) fn into_lrgba_data(self) -> Vec<u8> {
+		match self.to_lrgba().data {
+			ImageData::D8(data) => data,
+			_ => unreachable!()
+		}
+	}
+	
 	fn to_lrgba(self) -> Self {
 		if let ImageData::D8(data) = self.data {
 			let mut lrgba = Vec::with_capacity(data.len() / self.ty.components() * 4);
@@ -305,7 +324,7 @@ impl CacheIDLookup {
 }
 
 struct Upload {
-	result: Arc<Mutex<Option<Result<Coords, String>>>>,
+	result: Arc<Mutex<Option<Result<Coords, BasaltError>>>>,
 	condvar: Arc<Condvar>,
 	cache_id: SubImageCacheID,
 	image: Image,
@@ -318,20 +337,24 @@ impl Upload {
 		self.condvar.notify_one();
 	}
 	
-	fn err(&self, err: String) {
+	fn err(&self, err: AtlasError) {
 		let mut result = self.result.lock();
-		*result = Some(Err(err));
+		*result = Some(Err(err.into()));
 		self.condvar.notify_one();
 	}
 }
 
 pub struct Atlas {
-	basalt: Arc<Basalt>,
+	basalt: Weak<Basalt>,
 	cmd_queue: SegQueue<Command>,
-	draw_queue: SegQueue<HashMap<AtlasImageID, Arc<ImageViewAccess + Send + Sync>>>,
+	/// Version along with the latest images. The version increases every update.
+	draw_latest: Mutex<(u64, Option<HashMap<AtlasImageID, Arc<ImageViewAccess + Send + Sync>>>)>,
+	draw_info_version: Mutex<u64>,
 	empty_image: Arc<ImageViewAccess + Send + Sync>,
 	default_sampler: Arc<Sampler>,
 	unparker: Unparker,
+	idle: AtomicBool,
+	exited: AtomicBool,
 }
 
 impl Atlas {
@@ -357,15 +380,19 @@ impl Atlas {
 		let unparker = parker.unparker().clone();
 		
 		let atlas_ret = Arc::new(Atlas {
-			basalt, unparker,
+			basalt: Arc::downgrade(&basalt),
+			unparker,
 			default_sampler, empty_image,
-			draw_queue: SegQueue::new(),
+			draw_latest: Mutex::new((0, None)),
+			draw_info_version: Mutex::new(0),
 			cmd_queue: SegQueue::new(),
+			idle: AtomicBool::new(false),
+			exited: AtomicBool::new(false),
 		});
 		
 		let atlas = atlas_ret.clone();
 		
-		thread::spawn(move || {
+		basalt.track_thread(thread::spawn(move || {
 			let mut iter_start;
 			let mut atlas_images: Vec<AtlasImage> = Vec::new();
 			let mut sub_img_id_count = 1;
@@ -373,6 +400,15 @@ impl Atlas {
 			let mut execute = false;
 			
 			loop {
+				let basalt = match atlas.basalt.upgrade() {
+					Some(some) => some,
+					None => break
+				};
+				
+				if basalt.wants_exit() {
+					break;
+				}
+				
 				iter_start = Instant::now();
 				let mut cmds = Vec::new();
 				let mut got_cmd = false;
@@ -392,13 +428,16 @@ impl Atlas {
 							}
 							
 							if space_op.is_none() {
-								let atlas_image = AtlasImage::new(atlas.basalt.clone());
+								let atlas_image = AtlasImage::new(basalt.clone());
 								
 								match atlas_image.find_space_for(&upreq.image.dims) {
 									Some(region) => {
 										space_op = Some((atlas_images.len()+1, region));
 									}, None => {
-										upreq.err(format!("Image to big to fit in atlas."));
+										upreq.err(AtlasError::TooLarge {
+											w: upreq.image.dims.w,
+											h: upreq.image.dims.h,
+										});
 										continue;
 									}
 								}
@@ -422,7 +461,10 @@ impl Atlas {
 				}
 				
 				if !got_cmd && !execute {
+					drop(basalt);
+					atlas.idle.store(true, atomic::Ordering::SeqCst);
 					parker.park();
+					atlas.idle.store(false, atomic::Ordering::SeqCst);
 					continue;
 				}
 				
@@ -441,8 +483,8 @@ impl Atlas {
 				}
 				
 				let mut cmd_buf = AutoCommandBufferBuilder::new(
-					atlas.basalt.device(),
-					atlas.basalt.transfer_queue_ref().family()
+					basalt.device(),
+					basalt.transfer_queue_ref().family()
 				).unwrap();
 				
 				execute = false;
@@ -461,7 +503,7 @@ impl Atlas {
 				if execute {
 					drop(cmd_buf
 						.build().unwrap()
-						.execute(atlas.basalt.transfer_queue()).unwrap()
+						.execute(basalt.transfer_queue()).unwrap()
 						.then_signal_semaphore_and_flush().unwrap());
 					let mut draw_map = HashMap::new();
 						
@@ -471,10 +513,18 @@ impl Atlas {
 						}
 					}
 					
-					atlas.draw_queue.push(draw_map);
+					{
+						let mut draw_latest = atlas.draw_latest.lock();
+						draw_latest.0 += 1;
+						draw_latest.1 = Some(draw_map);
+					}
+					
+					for interface in basalt.interfaces() {
+						interface.odb.unpark();
+					}
 				}
 				
-				if PRINT_UPDATE_TIME && execute {
+				if execute && log_enabled!(target: logging::ATLAS, ::log::Level::Debug) {
 					let mut out = format!("Atlas Updated in {:.1} ms. ", iter_start.elapsed().as_micros() as f64 / 1000.0);
 
 					for (i, (w, h)) in sizes.into_iter().enumerate() {
@@ -482,15 +532,40 @@ impl Atlas {
 					}
 					
 					out.pop();
-					println!("{}", out);
+					debug!(target: logging::ATLAS, "{}", out);
 				}
 			
 			}
-		});
+			
+			atlas.exited.store(true, atomic::Ordering::SeqCst);
+			atlas.fail_pending();
+		}));
 		
 		atlas_ret
 	}
 	
+	/// Wake the atlas thread, so that it notices basalt is exiting.
+	pub(crate) fn wake(&self) {
+		self.unparker.unpark();
+	}
+	
+	/// Respond to commands that will never be processed because the thread has exited.
+	/// Called by both the thread and requesters, so no one is left waiting.
+	fn fail_pending(&self) {
+		while let Ok(cmd) = self.cmd_queue.pop() {
+			match cmd {
+				Command::Upload(upreq) => upreq.err(AtlasError::Exited),
+				Command::CacheIDLookup(clookup) => clookup.none(),
+				_ => ()
+			}
+		}
+	}
+	
+	/// True when there are no pending commands or uploads.
+	pub(crate) fn is_idle(&self) -> bool {
+		self.idle.load(atomic::Ordering::SeqCst)
+	}
+	
 	pub fn empty_image(&self) -> Arc<ImageViewAccess + Send + Sync> {
 		self.empty_image.clone()
 	}
@@ -500,13 +575,21 @@ impl Atlas {
 	}
 	
 	pub fn draw_info(&self) -> Option<HashMap<AtlasImageID, Arc<ImageViewAccess + Send + Sync>>> {
-		let mut out = None;
-
-		while let Ok(ok) = self.draw_queue.pop() {
-			out = Some(ok);
+		let mut version = self.draw_info_version.lock();
+		self.draw_info_since(&mut *version)
+	}
+	
+	/// The latest images if they have been updated since ``version``. Each interface keeps
+	/// its own version so that every one of them receives the update.
+	pub(crate) fn draw_info_since(&self, version: &mut u64) -> Option<HashMap<AtlasImageID, Arc<ImageViewAccess + Send + Sync>>> {
+		let draw_latest = self.draw_latest.lock();
+		
+		if draw_latest.0 == *version {
+			return None;
 		}
 		
-		out
+		*version = draw_latest.0;
+		draw_latest.1.clone()
 	}
 	
 	pub fn delete_sub_image(&self, sub_img_id: SubImageID) {
@@ -528,8 +611,13 @@ impl Atlas {
 		};
 		
 		self.cmd_queue.push(Command::CacheIDLookup(lookup));
+		self.idle.store(false, atomic::Ordering::SeqCst);
 		self.unparker.unpark();
 		
+		if self.exited.load(atomic::Ordering::SeqCst) {
+			self.fail_pending();
+		}
+		
 		let mut result = result.lock();
 		
 		while result.is_none() {
@@ -539,7 +627,7 @@ impl Atlas {
 		result.take().unwrap()
 	}
 	
-	pub fn load_image(&self, cache_id: SubImageCacheID, mut image: Image) -> Result<Coords, String> {
+	pub fn load_image(&self, cache_id: SubImageCacheID, mut image: Image) -> Result<Coords, BasaltError> {
 		image = image.to_lrgba();
 		let result = Arc::new(Mutex::new(None));
 		let condvar = Arc::new(Condvar::new());
@@ -551,8 +639,13 @@ impl Atlas {
 		};
 		
 		self.cmd_queue.push(Command::Upload(req));
+		self.idle.store(false, atomic::Ordering::SeqCst);
 		self.unparker.unpark();
 		
+		if self.exited.load(atomic::Ordering::SeqCst) {
+			self.fail_pending();
+		}
+		
 		let mut result = result.lock();
 		
 		while result.is_none() {
@@ -562,15 +655,15 @@ impl Atlas {
 		result.take().unwrap()
 	}
 	
-	pub fn load_image_from_bytes(&self, cache_id: SubImageCacheID, bytes: Vec<u8>) -> Result<Coords, String> {
+	pub fn load_image_from_bytes(&self, cache_id: SubImageCacheID, bytes: Vec<u8>) -> Result<Coords, BasaltError> {
 		let format = match image::guess_format(bytes.as_slice()) {
 			Ok(ok) => ok,
-			Err(e) => return Err(format!("Failed to guess image type for data: {}", e))
+			Err(e) => return Err(AtlasError::UnknownFormat(e).into())
 		};
 		
 		let (w, h, data) = match image::load_from_memory(bytes.as_slice()) {
 			Ok(image) => (image.width(), image.height(), image.to_rgba().into_vec()),
-			Err(e) => return Err(format!("Failed to read image: {}", e))
+			Err(e) => return Err(AtlasError::Decode(e).into())
 		};
 		
 		let image_type = match format {
@@ -578,12 +671,11 @@ impl Atlas {
 			_ => ImageType::LRGBA
 		};
 		
-		let image = Image::new(image_type, ImageDims { w, h }, ImageData::D8(data))
-			.map_err(|e| format!("Invalid Image: {}", e))?;
+		let image = Image::new(image_type, ImageDims { w, h }, ImageData::D8(data))?;
 		self.load_image(cache_id, image)
 	}
 	
-	pub fn load_image_from_path<P: Into<PathBuf>>(&self, path: P) -> Result<Coords, String> {
+	pub fn load_image_from_path<P: Into<PathBuf>>(&self, path: P) -> Result<Coords, BasaltError> {
 		let path_buf = path.into();
 		let cache_id = SubImageCacheID::Path(path_buf.clone());
 		
@@ -591,21 +683,21 @@ impl Atlas {
 			return Ok(coords);
 		}
 		
-		let mut handle = match File::open(path_buf) {
+		let mut handle = match File::open(&path_buf) {
 			Ok(ok) => ok,
-			Err(e) => return Err(format!("Failed to open file: {}", e))
+			Err(e) => return Err(AtlasError::Io(path_buf, e).into())
 		};
 			
 		let mut bytes = Vec::new();
 		
 		if let Err(e) = handle.read_to_end(&mut bytes) {
-			return Err(format!("Failed to read file: {}", e));
+			return Err(AtlasError::Io(path_buf, e).into());
 		}
 		
 		self.load_image_from_bytes(cache_id, bytes)
 	}
 	
-	pub fn load_image_from_url<U: AsRef<str>>(self: &Arc<Self>, url: U) -> Result<Coords, String> {
+	pub fn load_image_from_url<U: AsRef<str>>(self: &Arc<Self>, url: U) -> Result<Coords, BasaltError> {
 		let cache_id = SubImageCacheID::Url(url.as_ref().to_string());
 		
 		if let Some(coords) = self.cache_coords(cache_id.clone()) {
@@ -614,7 +706,8 @@ impl Atlas {
 		
 		let bytes = match ::misc::http::get_bytes(&url) {
 			Ok(ok) => ok,
-			Err(e) => return Err(format!("Failed to retreive url data: {}", e))
+			Err(BasaltError::Http(e)) => return Err(AtlasError::Url(url.as_ref().to_string(), e).into()),
+			Err(e) => return Err(e)
 		};
 		
 		self.load_image_from_bytes(cache_id, bytes)
@@ -0,0 +1,163 @@
+//! Access to the system clipboard.
+//!
+//! On Linux the X11 ``CLIPBOARD`` selection is used. When headless, on other platforms,
+//! or if connecting to the X server fails, an in-memory clipboard is used instead that is
+//! only shared within the process.
+
+use std::sync::Arc;
+use parking_lot::Mutex;
+use atlas::{Image,ImageDims,ImageData,ImageType};
+use error::{BasaltError,ClipboardError};
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use image::{self,DynamicImage,RgbaImage};
+#[cfg(target_os = "linux")]
+use x11_clipboard;
+#[cfg(target_os = "linux")]
+use logging;
+
+/// How long to wait for the selection owner to respond.
+#[cfg(target_os = "linux")]
+const LOAD_TIMEOUT: Duration = Duration::from_millis(500);
+
+enum Backend {
+	#[cfg(target_os = "linux")]
+	X11(x11_clipboard::Clipboard),
+	Memory(MemoryContent),
+}
+
+enum MemoryContent {
+	Empty,
+	Text(String),
+	Image(ImageDims, Vec<u8>),
+}
+
+pub struct Clipboard {
+	backend: Mutex<Backend>,
+}
+
+impl Clipboard {
+	pub(crate) fn new(use_system: bool) -> Arc<Self> {
+		Arc::new(Clipboard {
+			backend: Mutex::new(Self::backend(use_system)),
+		})
+	}
+	
+	#[cfg(target_os = "linux")]
+	fn backend(use_system: bool) -> Backend {
+		if use_system {
+			match x11_clipboard::Clipboard::new() {
+				Ok(ok) => return Backend::X11(ok),
+				Err(e) => warn!(target: logging::CLIPBOARD, "Failed to connect to the X11 clipboard, using an in-memory one: {}", e)
+			}
+		}
+		
+		Backend::Memory(MemoryContent::Empty)
+	}
+	
+	#[cfg(not(target_os = "linux"))]
+	fn backend(_use_system: bool) -> Backend {
+		Backend::Memory(MemoryContent::Empty)
+	}
+	
+	/// True if the clipboard is shared with other applications.
+	pub fn is_system(&self) -> bool {
+		match &*self.backend.lock() {
+			Backend::Memory(_) => false,
+			#[cfg(target_os = "linux")]
+			_ => true
+		}
+	}
+	
+	/// Text currently in the clipboard. ``None`` if the clipboard is empty or
+	/// doesn't contain text.
+	pub fn get_text(&self) -> Result<Option<String>, BasaltError> {
+		match &*self.backend.lock() {
+			#[cfg(target_os = "linux")]
+			Backend::X11(clipboard) => {
+				let atoms = &clipboard.getter.atoms;
+				let bytes = clipboard.load(atoms.clipboard, atoms.utf8_string, atoms.property, LOAD_TIMEOUT)
+					.map_err(|e| ClipboardError::X11(e))?;
+				
+				if bytes.is_empty() {
+					return Ok(None);
+				}
+				
+				String::from_utf8(bytes).map(|text| Some(text)).map_err(|e| ClipboardError::NotUtf8(e).into())
+			},
+			Backend::Memory(MemoryContent::Text(text)) => Ok(Some(text.clone())),
+			Backend::Memory(_) => Ok(None)
+		}
+	}
+	
+	pub fn set_text<T: Into<String>>(&self, text: T) -> Result<(), BasaltError> {
+		let text = text.into();
+		
+		match &mut *self.backend.lock() {
+			#[cfg(target_os = "linux")]
+			Backend::X11(clipboard) => {
+				let atoms = &clipboard.setter.atoms;
+				clipboard.store(atoms.clipboard, atoms.utf8_string, text).map_err(|e| ClipboardError::X11(e).into())
+			},
+			Backend::Memory(content) => {
+				*content = MemoryContent::Text(text);
+				Ok(())
+			}
+		}
+	}
+	
+	/// Image currently in the clipboard as ``ImageType::LRGBA``, which can be loaded with
+	/// ``Atlas::load_image()``. ``None`` if the clipboard is empty or doesn't contain a png.
+	pub fn get_image(&self) -> Result<Option<Image>, BasaltError> {
+		let (dims, data) = match &*self.backend.lock() {
+			#[cfg(target_os = "linux")]
+			Backend::X11(clipboard) => {
+				let atoms = &clipboard.getter.atoms;
+				let png = clipboard.getter.get_atom("image/png").map_err(|e| ClipboardError::X11(e))?;
+				let bytes = clipboard.load(atoms.clipboard, png, atoms.property, LOAD_TIMEOUT)
+					.map_err(|e| ClipboardError::X11(e))?;
+				
+				if bytes.is_empty() {
+					return Ok(None);
+				}
+				
+				let decoded = image::load_from_memory_with_format(bytes.as_slice(), image::ImageFormat::PNG)
+					.map_err(|e| ClipboardError::Decode(e))?.to_rgba();
+				(ImageDims { w: decoded.width(), h: decoded.height() }, decoded.into_raw())
+			},
+			Backend::Memory(MemoryContent::Image(dims, data)) => (*dims, data.clone()),
+			Backend::Memory(_) => return Ok(None)
+		};
+		
+		Image::new(ImageType::LRGBA, dims, ImageData::D8(data)).map(|image| Some(image))
+	}
+	
+	/// Place an image in the clipboard. Other applications receive it as a png.
+	pub fn set_image(&self, image: Image) -> Result<(), BasaltError> {
+		let dims = image.dims();
+		let data = image.into_lrgba_data();
+		
+		match &mut *self.backend.lock() {
+			#[cfg(target_os = "linux")]
+			Backend::X11(clipboard) => {
+				let expected = dims.w as usize * dims.h as usize * 4;
+				let actual = data.len();
+				
+				let rgba = match RgbaImage::from_raw(dims.w, dims.h, data) {
+					Some(some) => some,
+					None => return Err(ClipboardError::ImageDataLength { expected, actual }.into())
+				};
+				
+				let mut png = Vec::new();
+				DynamicImage::ImageRgba8(rgba).write_to(&mut png, image::ImageOutputFormat::PNG).map_err(|e| ClipboardError::Encode(e))?;
+				let target = clipboard.setter.get_atom("image/png").map_err(|e| ClipboardError::X11(e))?;
+				clipboard.store(clipboard.setter.atoms.clipboard, target, png).map_err(|e| ClipboardError::X11(e).into())
+			},
+			Backend::Memory(content) => {
+				*content = MemoryContent::Image(dims, data);
+				Ok(())
+			}
+		}
+	}
+}
@@ -0,0 +1,174 @@
+//! Vulkan validation layers and debug report messages.
+//!
+//! Enabled with ``Options::validation()``. Messages at or above the severity set with
+//! ``Options::debug_severity()`` are passed to the callback set with
+//! ``Options::debug_callback()``, or logged with the ``logging::VULKAN`` target if there
+//! isn't one.
+
+use std::sync::Arc;
+use std::fmt;
+use std::panic::AssertUnwindSafe;
+use vulkano::instance::{self,Instance,InstanceExtensions};
+use vulkano::instance::debug::{DebugCallback,Message,MessageTypes};
+use error::{BasaltError,InitError};
+use logging;
+use log::Level;
+
+/// Checked in order, the first one available is used. The LunarG layer is the name used by
+/// older SDK's.
+const VALIDATION_LAYERS: [&str; 2] = [
+	"VK_LAYER_KHRONOS_validation",
+	"VK_LAYER_LUNARG_standard_validation",
+];
+
+pub type DebugCallbackFn = Arc<Fn(&DebugMessage) + Send + Sync>;
+
+/// Severity of a debug message, ordered from most to least severe.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord)]
+pub enum DebugSeverity {
+	/// Invalid usage that may cause undefined results, including crashes.
+	Error,
+	/// Unexpected usage.
+	Warning,
+	/// Usage that is likely to be slow.
+	PerformanceWarning,
+	Information,
+	/// Diagnostics from the loader and layers.
+	Debug,
+}
+
+impl DebugSeverity {
+	fn of(ty: &MessageTypes) -> Self {
+		if ty.error {
+			DebugSeverity::Error
+		} else if ty.warning {
+			DebugSeverity::Warning
+		} else if ty.performance_warning {
+			DebugSeverity::PerformanceWarning
+		} else if ty.information {
+			DebugSeverity::Information
+		} else {
+			DebugSeverity::Debug
+		}
+	}
+	
+	fn level(self) -> Level {
+		match self {
+			DebugSeverity::Error => Level::Error,
+			DebugSeverity::Warning | DebugSeverity::PerformanceWarning => Level::Warn,
+			DebugSeverity::Information => Level::Info,
+			DebugSeverity::Debug => Level::Debug,
+		}
+	}
+	
+	/// Message types of this severity and anything more severe.
+	fn message_types(self) -> MessageTypes {
+		MessageTypes {
+			error: true,
+			warning: self >= DebugSeverity::Warning,
+			performance_warning: self >= DebugSeverity::PerformanceWarning,
+			information: self >= DebugSeverity::Information,
+			debug: self >= DebugSeverity::Debug,
+		}
+	}
+}
+
+#[derive(Debug,Clone)]
+pub struct DebugMessage {
+	pub severity: DebugSeverity,
+	/// Prefix of the layer that reported the message.
+	pub layer_prefix: String,
+	pub description: String,
+}
+
+#[derive(Clone)]
+pub(crate) struct DebugOptions {
+	pub(crate) validation: bool,
+	pub(crate) severity: DebugSeverity,
+	pub(crate) callback: Option<DebugCallbackFn>,
+}
+
+impl Default for DebugOptions {
+	fn default() -> Self {
+		DebugOptions {
+			validation: false,
+			severity: DebugSeverity::Warning,
+			callback: None,
+		}
+	}
+}
+
+impl fmt::Debug for DebugOptions {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DebugOptions {{ validation: {}, severity: {:?}, callback: {} }}",
+			self.validation, self.severity, if self.callback.is_some() { "Some(..)" } else { "None" })
+	}
+}
+
+/// Keeps the debug callback registered for as long as it is alive.
+pub(crate) struct Reporter(DebugCallback);
+
+// The user callback inside is required to be Send + Sync, the rest is only destroyed on drop
+// which the Vulkan spec allows from any thread.
+unsafe impl Send for Reporter {}
+unsafe impl Sync for Reporter {}
+
+/// Create the instance with ``extensions``. When validation is enabled, the validation layer
+/// and debug report extension are also enabled if available.
+pub(crate) fn create_instance(
+	mut extensions: InstanceExtensions,
+	options: &DebugOptions
+) -> Result<(Arc<Instance>, Option<Reporter>), BasaltError> {
+	let mut layer = None;
+	
+	if options.validation {
+		let available: Vec<String> = match instance::layers_list() {
+			Ok(layers) => layers.map(|layer| layer.name().to_string()).collect(),
+			Err(_) => Vec::new()
+		};
+		
+		layer = VALIDATION_LAYERS.iter().find(|name| available.iter().any(|a| a.as_str() == **name)).cloned();
+		
+		if layer.is_none() {
+			warn!(target: logging::INIT, "Validation requested, but no validation layer is installed.");
+		}
+		
+		if let Ok(supported) = InstanceExtensions::supported_by_core() {
+			extensions.ext_debug_report = supported.ext_debug_report;
+		}
+	}
+	
+	let instance = match Instance::new(None, &extensions, layer) {
+		Ok(ok) => ok,
+		Err(e) => return Err(InitError::CreateInstance(e).into())
+	};
+	
+	if !options.validation {
+		return Ok((instance, None));
+	}
+	
+	if !instance.loaded_extensions().ext_debug_report {
+		warn!(target: logging::INIT, "Validation requested, but the debug report extension isn't available.");
+		return Ok((instance, None));
+	}
+	
+	let callback = AssertUnwindSafe(options.callback.clone());
+	
+	let reporter = match DebugCallback::new(&instance, options.severity.message_types(), move |msg: &Message| {
+		let message = DebugMessage {
+			severity: DebugSeverity::of(&msg.ty),
+			layer_prefix: msg.layer_prefix.to_string(),
+			description: msg.description.to_string(),
+		};
+		
+		match &callback.0 {
+			Some(func) => func(&message),
+			None => log!(target: logging::VULKAN, message.severity.level(), "[{}] {}", message.layer_prefix, message.description)
+		}
+	}) {
+		Ok(ok) => ok,
+		Err(e) => return Err(InitError::CreateDebugCallback(e).into())
+	};
+	
+	Ok((instance, Some(Reporter(reporter))))
+}
@@ -0,0 +1,453 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::string::FromUtf8Error;
+use vulkano::instance::{InstanceCreationError,PhysicalDeviceType};
+use vulkano::device::{DeviceCreationError,Features};
+use vulkano::instance::debug::DebugCallbackCreationError;
+use vulkano::swapchain::{CapabilitiesError,ColorSpace};
+use vulkano::memory::DeviceMemoryAllocError;
+use vulkano::image::ImageCreationError;
+use vulkano::format::Format;
+use vulkano::sync::FlushError;
+use vulkano::OomError;
+use vulkano::command_buffer::{BuildError,CommandBufferExecError,CopyBufferImageError};
+use vulkano::buffer::cpu_access::ReadLockError;
+use vulkano_win::CreationError as WindowCreationError;
+use winit::BadIcon;
+
+/// Error returned by all fallible Basalt API's.
+#[derive(Debug)]
+pub enum BasaltError {
+	Init(InitError),
+	Render(RenderError),
+	Atlas(AtlasError),
+	Text(TextError),
+	Input(InputError),
+	Http(HttpError),
+	Window(WindowError),
+	Clipboard(ClipboardError),
+	Prefs(PrefsError),
+}
+
+#[derive(Debug)]
+pub enum InitError {
+	CreateInstance(InstanceCreationError),
+	NoPhysicalDevices,
+	PhysicalDeviceNotFound(usize),
+	NoDeviceNamed(String),
+	NoDeviceAccepted,
+	NoDeviceOfType(Vec<PhysicalDeviceType>),
+	CreateWindow(WindowCreationError),
+	SurfaceCapabilities(CapabilitiesError),
+	NoGraphicsQueue,
+	CreateDevice(DeviceCreationError),
+	NoQueues,
+	/// Basalt doesn't have a window, so there is no event loop to create windows on.
+	NoEventLoop,
+	CreateDebugCallback(DebugCallbackCreationError),
+	/// Features required by ``Options::device_features()`` the device doesn't support.
+	MissingFeatures(Features),
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+	InvalidMSAA(u32),
+	NoSwapchainFormat(Vec<(Format, ColorSpace)>),
+	/// The surface doesn't support any composite alpha mode.
+	NoCompositeAlpha,
+	CreateBuffer(DeviceMemoryAllocError),
+	CreateImage(ImageCreationError),
+	CreateCommandBuffer(OomError),
+	BuildCommandBuffer(BuildError),
+	Execute(CommandBufferExecError),
+	Flush(FlushError),
+	ReadBuffer(ReadLockError),
+	CopyImage(CopyBufferImageError),
+	/// The image to read back doesn't have the dimensions the readback was created for.
+	ReadbackSize {
+		expected: [u32; 2],
+		actual: [u32; 2],
+	},
+	/// The data read back doesn't match the readback's dimensions.
+	ReadbackDataLength {
+		expected: usize,
+		actual: usize,
+	},
+	/// Recording the frame failed, so it was never drawn or captured.
+	FrameDropped,
+	/// The interface wasn't rendered to an image that can be read back.
+	NoTarget,
+	LoopThreadPanicked,
+	/// The area to capture has no size.
+	EmptyCapture,
+	SaveImage(PathBuf, io::Error),
+	/// The window was closed before the frame could be captured.
+	WindowClosed,
+}
+
+#[derive(Debug)]
+pub enum AtlasError {
+	EmptyImage,
+	DataLength {
+		expected: usize,
+		actual: usize,
+	},
+	TooLarge {
+		w: u32,
+		h: u32,
+	},
+	UnknownFormat(::image::ImageError),
+	Decode(::image::ImageError),
+	Io(PathBuf, io::Error),
+	Url(String, HttpError),
+	/// The atlas thread has exited because basalt is shutting down.
+	Exited,
+}
+
+#[derive(Debug)]
+pub enum TextError {
+	/// A FreeType function returned a non-zero error code.
+	FreeType {
+		function: &'static str,
+		code: i32,
+	},
+}
+
+#[derive(Debug)]
+pub enum InputError {
+	/// The input thread is no longer receiving events.
+	Disconnected,
+}
+
+#[derive(Debug)]
+pub enum WindowError {
+	/// Basalt doesn't have a window.
+	NoWindow,
+	BadIcon(BadIcon),
+	MonitorNotFound(usize),
+}
+
+#[derive(Debug)]
+pub enum ClipboardError {
+	#[cfg(target_os = "linux")]
+	X11(::x11_clipboard::error::Error),
+	/// The clipboard contains text that isn't valid utf8.
+	NotUtf8(FromUtf8Error),
+	Decode(::image::ImageError),
+	Encode(::image::ImageError),
+	/// The image's data doesn't match its dimensions.
+	ImageDataLength {
+		expected: usize,
+		actual: usize,
+	},
+}
+
+#[derive(Debug)]
+pub enum PrefsError {
+	Io(PathBuf, io::Error),
+	Parse(PathBuf, ::toml::de::Error),
+	Serialize(::toml::ser::Error),
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+	InvalidUrl(curl::Error),
+	WriteFunction(curl::Error),
+	Perform(curl::Error),
+}
+
+impl fmt::Display for BasaltError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			BasaltError::Init(e) => write!(f, "Initialization error: {}", e),
+			BasaltError::Render(e) => write!(f, "Render error: {}", e),
+			BasaltError::Atlas(e) => write!(f, "Atlas error: {}", e),
+			BasaltError::Text(e) => write!(f, "Text error: {}", e),
+			BasaltError::Input(e) => write!(f, "Input error: {}", e),
+			BasaltError::Http(e) => write!(f, "Http error: {}", e),
+			BasaltError::Window(e) => write!(f, "Window error: {}", e),
+			BasaltError::Clipboard(e) => write!(f, "Clipboard error: {}", e),
+			BasaltError::Prefs(e) => write!(f, "Preferences error: {}", e),
+		}
+	}
+}
+
+impl fmt::Display for InitError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			InitError::CreateInstance(e) => write!(f, "Failed to create instance: {}", e),
+			InitError::NoPhysicalDevices => write!(f, "No physical devices available."),
+			InitError::PhysicalDeviceNotFound(i) => write!(f, "Physical device {} not found.", i),
+			InitError::NoDeviceNamed(name) => write!(f, "No physical device name contains '{}'.", name),
+			InitError::NoDeviceAccepted => write!(f, "No physical device was accepted by the custom selector."),
+			InitError::NoDeviceOfType(types) => write!(f, "No physical device is of the types {:?}.", types),
+			InitError::CreateWindow(e) => write!(f, "Failed to build window: {}", e),
+			InitError::SurfaceCapabilities(e) => write!(f, "Failed to get surface capabilities: {}", e),
+			InitError::NoGraphicsQueue => write!(f, "Couldn't find a suitable queue for graphics."),
+			InitError::CreateDevice(e) => write!(f, "Failed to create device: {}", e),
+			InitError::NoQueues => write!(f, "Device didn't have any queues."),
+			InitError::NoEventLoop => write!(f, "Windows can't be created without an event loop."),
+			InitError::CreateDebugCallback(e) => write!(f, "Failed to create debug callback: {}", e),
+			InitError::MissingFeatures(features) => write!(f, "Device doesn't support required features: {:?}", features),
+		}
+	}
+}
+
+impl fmt::Display for RenderError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RenderError::InvalidMSAA(amt) => write!(f, "Invalid MSAA amount {}X", amt),
+			RenderError::NoSwapchainFormat(formats) => write!(f, "Failed to find capatible format for swapchain. Avaible formats: {:?}", formats),
+			RenderError::NoCompositeAlpha => write!(f, "The surface doesn't support any composite alpha mode."),
+			RenderError::CreateBuffer(e) => write!(f, "Failed to create buffer: {}", e),
+			RenderError::CreateImage(e) => write!(f, "Failed to create image: {}", e),
+			RenderError::CreateCommandBuffer(e) => write!(f, "Failed to create command buffer: {}", e),
+			RenderError::BuildCommandBuffer(e) => write!(f, "Failed to build command buffer: {}", e),
+			RenderError::Execute(e) => write!(f, "Failed to execute command buffer: {}", e),
+			RenderError::Flush(e) => write!(f, "Failed to flush: {}", e),
+			RenderError::ReadBuffer(e) => write!(f, "Failed to read buffer: {}", e),
+			RenderError::CopyImage(e) => write!(f, "Failed to copy image to buffer: {}", e),
+			RenderError::ReadbackSize { expected, actual } => write!(f, "Image of {}x{} can't be read back into a buffer for {}x{}.", actual[0], actual[1], expected[0], expected[1]),
+			RenderError::ReadbackDataLength { expected, actual } => write!(f, "Read back {} bytes, expected {}.", actual, expected),
+			RenderError::FrameDropped => write!(f, "The frame was dropped because recording it failed."),
+			RenderError::NoTarget => write!(f, "The interface wasn't rendered to an image."),
+			RenderError::LoopThreadPanicked => write!(f, "Failed to join loop thread."),
+			RenderError::EmptyCapture => write!(f, "Nothing to capture, area has no size."),
+			RenderError::SaveImage(path, e) => write!(f, "Failed to save image to {}: {}", path.display(), e),
+			RenderError::WindowClosed => write!(f, "The window has been closed."),
+		}
+	}
+}
+
+impl fmt::Display for AtlasError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			AtlasError::EmptyImage => write!(f, "Image can't be empty."),
+			AtlasError::DataLength { expected, actual } => write!(f, "Data length of {} doesn't match the provided dimensions, expected {}.", actual, expected),
+			AtlasError::TooLarge { w, h } => write!(f, "Image of {}x{} is to big to fit in atlas.", w, h),
+			AtlasError::UnknownFormat(e) => write!(f, "Failed to guess image type for data: {}", e),
+			AtlasError::Decode(e) => write!(f, "Failed to read image: {}", e),
+			AtlasError::Io(path, e) => write!(f, "Failed to read file {}: {}", path.display(), e),
+			AtlasError::Url(url, e) => write!(f, "Failed to retreive url {}: {}", url, e),
+			AtlasError::Exited => write!(f, "The atlas has exited."),
+		}
+	}
+}
+
+impl fmt::Display for TextError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			TextError::FreeType { function, code } => write!(f, "{}: error {}", function, code),
+		}
+	}
+}
+
+impl fmt::Display for InputError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			InputError::Disconnected => write!(f, "Input thread is no longer running."),
+		}
+	}
+}
+
+impl fmt::Display for WindowError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			WindowError::NoWindow => write!(f, "Basalt doesn't have a window."),
+			WindowError::BadIcon(e) => write!(f, "Invalid icon: {}", e),
+			WindowError::MonitorNotFound(i) => write!(f, "Monitor {} not found.", i),
+		}
+	}
+}
+
+impl fmt::Display for ClipboardError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			#[cfg(target_os = "linux")]
+			ClipboardError::X11(e) => write!(f, "X11: {}", e),
+			ClipboardError::NotUtf8(e) => write!(f, "Text isn't valid utf8: {}", e),
+			ClipboardError::Decode(e) => write!(f, "Failed to read image: {}", e),
+			ClipboardError::Encode(e) => write!(f, "Failed to encode image: {}", e),
+			ClipboardError::ImageDataLength { expected, actual } => write!(f, "Image has {} bytes, expected {}.", actual, expected),
+		}
+	}
+}
+
+impl fmt::Display for PrefsError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			PrefsError::Io(path, e) => write!(f, "Failed to access {}: {}", path.display(), e),
+			PrefsError::Parse(path, e) => write!(f, "Failed to parse {}: {}", path.display(), e),
+			PrefsError::Serialize(e) => write!(f, "Failed to serialize: {}", e),
+		}
+	}
+}
+
+impl fmt::Display for HttpError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			HttpError::InvalidUrl(e) => write!(f, "bad url: {}", e),
+			HttpError::WriteFunction(e) => write!(f, "write function: {}", e),
+			HttpError::Perform(e) => write!(f, "failed to perform: {}", e),
+		}
+	}
+}
+
+impl Error for BasaltError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			BasaltError::Init(e) => Some(e),
+			BasaltError::Render(e) => Some(e),
+			BasaltError::Atlas(e) => Some(e),
+			BasaltError::Text(e) => Some(e),
+			BasaltError::Input(e) => Some(e),
+			BasaltError::Http(e) => Some(e),
+			BasaltError::Window(e) => Some(e),
+			BasaltError::Clipboard(e) => Some(e),
+			BasaltError::Prefs(e) => Some(e),
+		}
+	}
+}
+
+impl Error for InitError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			InitError::CreateInstance(e) => Some(e),
+			InitError::CreateWindow(e) => Some(e),
+			InitError::SurfaceCapabilities(e) => Some(e),
+			InitError::CreateDevice(e) => Some(e),
+			InitError::CreateDebugCallback(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl Error for RenderError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			RenderError::CreateBuffer(e) => Some(e),
+			RenderError::CreateImage(e) => Some(e),
+			RenderError::CreateCommandBuffer(e) => Some(e),
+			RenderError::BuildCommandBuffer(e) => Some(e),
+			RenderError::Execute(e) => Some(e),
+			RenderError::Flush(e) => Some(e),
+			RenderError::ReadBuffer(e) => Some(e),
+			RenderError::CopyImage(e) => Some(e),
+			RenderError::SaveImage(_, e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl Error for AtlasError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			AtlasError::UnknownFormat(e) => Some(e),
+			AtlasError::Decode(e) => Some(e),
+			AtlasError::Io(_, e) => Some(e),
+			AtlasError::Url(_, e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl Error for WindowError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			WindowError::BadIcon(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl Error for ClipboardError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			#[cfg(target_os = "linux")]
+			ClipboardError::X11(e) => Some(e),
+			ClipboardError::NotUtf8(e) => Some(e),
+			ClipboardError::Decode(e) => Some(e),
+			ClipboardError::Encode(e) => Some(e),
+			ClipboardError::ImageDataLength { .. } => None,
+		}
+	}
+}
+
+impl Error for PrefsError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			PrefsError::Io(_, e) => Some(e),
+			PrefsError::Parse(_, e) => Some(e),
+			PrefsError::Serialize(e) => Some(e),
+		}
+	}
+}
+
+impl Error for TextError {}
+impl Error for InputError {}
+
+impl Error for HttpError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			HttpError::InvalidUrl(e) => Some(e),
+			HttpError::WriteFunction(e) => Some(e),
+			HttpError::Perform(e) => Some(e),
+		}
+	}
+}
+
+impl From<InitError> for BasaltError {
+	fn from(e: InitError) -> Self {
+		BasaltError::Init(e)
+	}
+}
+
+impl From<RenderError> for BasaltError {
+	fn from(e: RenderError) -> Self {
+		BasaltError::Render(e)
+	}
+}
+
+impl From<AtlasError> for BasaltError {
+	fn from(e: AtlasError) -> Self {
+		BasaltError::Atlas(e)
+	}
+}
+
+impl From<TextError> for BasaltError {
+	fn from(e: TextError) -> Self {
+		BasaltError::Text(e)
+	}
+}
+
+impl From<InputError> for BasaltError {
+	fn from(e: InputError) -> Self {
+		BasaltError::Input(e)
+	}
+}
+
+impl From<WindowError> for BasaltError {
+	fn from(e: WindowError) -> Self {
+		BasaltError::Window(e)
+	}
+}
+
+impl From<ClipboardError> for BasaltError {
+	fn from(e: ClipboardError) -> Self {
+		BasaltError::Clipboard(e)
+	}
+}
+
+impl From<PrefsError> for BasaltError {
+	fn from(e: PrefsError) -> Self {
+		BasaltError::Prefs(e)
+	}
+}
+
+impl From<HttpError> for BasaltError {
+	fn from(e: HttpError) -> Self {
+		BasaltError::Http(e)
+	}
+}
@@ -5,13 +5,14 @@ pub use self::qwery::*;
 
 use std::time::Duration;
 use std::thread;
-use std::sync::Arc;
+use std::sync::{Arc,Weak};
 use std::time::Instant;
 use Basalt;
 use crossbeam::channel::{self,Sender};
 use std::collections::{BTreeMap,HashMap};
 use std::sync::atomic::{self,AtomicUsize};
 use BasaltEvent;
+use error::{BasaltError,InputError};
 use interface::hook::InputEvent as ItfInputEvent;
 
 pub type InputHookID = u64;
@@ -85,6 +86,8 @@ pub enum InputHook {
 	MouseMotion,
 	/// Called when the mouse is over the window.
 	MouseScroll,
+	/// Called on horizontal scrolling when the mouse is over the window.
+	MouseScrollX,
 	/// Called when the window gains focus.
 	WindowFocused,
 	/// Called when the window loses focus.
@@ -250,6 +253,14 @@ impl InputHook {
 				}
 			},
 			
+			InputHook::MouseScrollX => {
+				InputHookData::MouseScrollX {
+					scroll_amt: 0.0,
+					mouse_x: 0.0,
+					mouse_y: 0.0,
+				}
+			},
+			
 			InputHook::WindowFocused => InputHookData::WindowFocused,
 			InputHook::WindowLostFocus => InputHookData::WindowLostFocus,
 			
@@ -320,6 +331,7 @@ impl InputHook {
 			InputHook::MouseMove => InputHookTy::MouseMove,
 			InputHook::MouseMotion { .. } => InputHookTy::MouseMotion,
 			InputHook::MouseScroll => InputHookTy::MouseScroll,
+			InputHook::MouseScrollX => InputHookTy::MouseScrollX,
 			InputHook::WindowFocused => InputHookTy::WindowFocused,
 			InputHook::WindowLostFocus => InputHookTy::WindowLostFocus,
 			InputHook::AnyMouseOrKeyPress { .. } => InputHookTy::AnyMouseOrKeyPress,
@@ -343,6 +355,7 @@ pub enum InputHookTy {
 	MouseMove,
 	MouseMotion,
 	MouseScroll,
+	MouseScrollX,
 	WindowFocused,
 	WindowLostFocus,
 	AnyMouseOrKeyPress,
@@ -409,6 +422,11 @@ pub enum InputHookData {
 		mouse_y: f32,
 		scroll_amt: f32,
 	},
+	MouseScrollX {
+		mouse_x: f32,
+		mouse_y: f32,
+		scroll_amt: f32,
+	},
 	WindowFocused,
 	WindowLostFocus,
 	AnyMouseOrKeyPress {
@@ -449,6 +467,7 @@ impl InputHookData {
 			InputHookData::MouseMove { .. } => InputHookTy::MouseMove,
 			InputHookData::MouseMotion { .. } => InputHookTy::MouseMotion,
 			InputHookData::MouseScroll { .. } => InputHookTy::MouseScroll,
+			InputHookData::MouseScrollX { .. } => InputHookTy::MouseScrollX,
 			InputHookData::WindowFocused => InputHookTy::WindowFocused,
 			InputHookData::WindowLostFocus => InputHookTy::WindowLostFocus,
 			InputHookData::AnyMouseOrKeyPress { .. } => InputHookTy::AnyMouseOrKeyPress,
@@ -530,7 +549,10 @@ pub enum Event {
 	MouseRelease(MouseButton),
 	MouseMotion(f32, f32),
 	MousePosition(f32, f32),
+	/// Positive scrolls down.
 	MouseScroll(f32),
+	/// Positive scrolls right.
+	MouseScrollX(f32),
 	MouseEnter,
 	MouseLeave,
 	WindowResized,
@@ -542,24 +564,31 @@ pub enum Event {
 }
 
 pub struct Input {
-	basalt: Arc<Basalt>,
+	basalt: Weak<Basalt>,
 	event_send: Sender<Event>,
 	hook_id_count: AtomicUsize,
 }
 
 impl Input {
 	pub fn send_event(&self, event: Event) {
-		self.event_send.send(event).unwrap();
+		self.try_send_event(event).unwrap();
+	}
+	
+	/// Same as ``send_event()`` but returns an error instead of panicking if the input
+	/// thread is no longer running.
+	pub fn try_send_event(&self, event: Event) -> Result<(), BasaltError> {
+		self.event_send.send(event).map_err(|_| InputError::Disconnected.into())
 	}
 	
 	pub fn add_hook(&self, hook: InputHook, func: InputHookFn) -> InputHookID {
 		let id = self.hook_id_count.fetch_add(1, atomic::Ordering::SeqCst) as u64;
-		self.event_send.send(Event::AddHook(id, hook, func)).unwrap();
+		// Once the input thread has exited hooks are never called, so failing to send is fine.
+		let _ = self.event_send.send(Event::AddHook(id, hook, func));
 		id
 	}
 	
 	pub fn remove_hook(&self, id: InputHookID) {
-		self.event_send.send(Event::DelHook(id)).unwrap();
+		let _ = self.event_send.send(Event::DelHook(id));
 	}
 
 	pub fn on_key_press(&self, key: Qwery, func: InputHookFn) -> InputHookID {
@@ -656,7 +685,7 @@ impl Input {
 		let (event_send, event_recv) = channel::unbounded();
 	
 		let input_ret = Arc::new(Input {
-			basalt,
+			basalt: Arc::downgrade(&basalt),
 			event_send,
 			hook_id_count: AtomicUsize::new(0),
 		});
@@ -665,8 +694,8 @@ impl Input {
 		
 		unsafe {
 			use std::mem::transmute;
-
-			let interface = input.basalt.interface();
+			
+			let interface = basalt.interface();
 			input.add_hook(InputHook::AnyKeyPress { global: false }, Arc::new(move |data| {
 				if let InputHookData::AnyKeyPress { key, .. } = data {
 					interface.hook_manager.send_event(ItfInputEvent::KeyPress(key.clone()));
@@ -675,7 +704,7 @@ impl Input {
 				InputHookRes::Success
 			}));
 			
-			let interface = input.basalt.interface();
+			let interface = basalt.interface();
 			input.add_hook(InputHook::AnyKeyRelease { global: false }, Arc::new(move |data| {
 				if let InputHookData::AnyKeyRelease { key, .. } = data {
 					interface.hook_manager.send_event(ItfInputEvent::KeyRelease(key.clone()));
@@ -684,7 +713,7 @@ impl Input {
 				InputHookRes::Success
 			}));
 			
-			let interface = input.basalt.interface();
+			let interface = basalt.interface();
 			input.add_hook(InputHook::AnyMousePress { global: false }, Arc::new(move |data| {
 				if let InputHookData::AnyMousePress { button, .. } = data {
 					interface.hook_manager.send_event(ItfInputEvent::MousePress(transmute(button.clone())));
@@ -693,7 +722,7 @@ impl Input {
 				InputHookRes::Success
 			}));
 			
-			let interface = input.basalt.interface();
+			let interface = basalt.interface();
 			input.add_hook(InputHook::AnyMouseRelease { global: false }, Arc::new(move |data| {
 				if let InputHookData::AnyMouseRelease { button, .. } = data {
 					interface.hook_manager.send_event(ItfInputEvent::MouseRelease(transmute(button.clone())));
@@ -702,7 +731,7 @@ impl Input {
 				InputHookRes::Success
 			}));
 			
-			let interface = input.basalt.interface();
+			let interface = basalt.interface();
 			input.add_hook(InputHook::MouseMove, Arc::new(move |data| {
 				if let InputHookData::MouseMove { 
 					mouse_x,
@@ -717,7 +746,7 @@ impl Input {
 				InputHookRes::Success
 			}));
 			
-			let interface = input.basalt.interface();
+			let interface = basalt.interface();
 			input.add_hook(InputHook::MouseScroll, Arc::new(move |data| {
 				if let InputHookData::MouseScroll { scroll_amt, .. } = data {
 					interface.hook_manager.send_event(ItfInputEvent::Scroll(*scroll_amt));
@@ -725,9 +754,18 @@ impl Input {
 				
 				InputHookRes::Success
 			}));
+			
+			let interface = basalt.interface();
+			input.add_hook(InputHook::MouseScrollX, Arc::new(move |data| {
+				if let InputHookData::MouseScrollX { scroll_amt, .. } = data {
+					interface.hook_manager.send_event(ItfInputEvent::ScrollX(*scroll_amt));
+				}
+				
+				InputHookRes::Success
+			}));
 		}
 		
-		thread::spawn(move || {
+		basalt.track_thread(thread::spawn(move || {
 			let mut key_state: HashMap<Qwery, bool> = HashMap::new();
 			let mut mouse_state = HashMap::new();
 			let mut global_key_state = HashMap::new();
@@ -739,6 +777,15 @@ impl Input {
 			let mut hook_map: BTreeMap<InputHookID, (InputHookData, InputHookFn)> = BTreeMap::new();
 		
 			loop {
+				let basalt = match input.basalt.upgrade() {
+					Some(some) => some,
+					None => break
+				};
+				
+				if basalt.wants_exit() {
+					break;
+				}
+				
 				let start = Instant::now();
 				let mut mouse_motion_x = 0.0;
 				let mut mouse_motion_y = 0.0;
@@ -746,6 +793,8 @@ impl Input {
 				let mut mouse_moved = false;
 				let mut m_scroll_amt = 0.0;
 				let mut scrolled = false;
+				let mut m_scroll_x_amt = 0.0;
+				let mut scrolled_x = false;
 				let mut events = Vec::new();
 
 				while let Ok(event) = event_recv.try_recv() {
@@ -766,6 +815,13 @@ impl Input {
 					}
 				});
 				
+				if events.iter().any(|e| match e {
+					Event::MouseMotion(..) | Event::MousePosition(..) => false,
+					_ => true
+				}) {
+					basalt.request_redraw();
+				}
+				
 				let mut window_focus_lost = false;
 				
 				events.retain(|e| {
@@ -791,15 +847,16 @@ impl Input {
 							false
 						},
 						Event::WindowResized => {
-							input.basalt.send_event(BasaltEvent::WindowResized);
+							basalt.send_event(BasaltEvent::WindowResized);
 							false
 						},
 						Event::WindowDPIChange(dpi) => {
-							input.basalt.send_event(BasaltEvent::DPIChanged(*dpi));
+							basalt.send_event(BasaltEvent::DPIChanged(*dpi));
 							false
 						}
 						Event::WindowFocused => {
 							window_focused = true;
+							basalt.send_event(BasaltEvent::Focused);
 							
 							for (_hook_id, (hook_data, hook_func)) in &hook_map {
 								if hook_data.ty() == InputHookTy::WindowFocused {
@@ -812,6 +869,7 @@ impl Input {
 						Event::WindowLostFocus => {
 							window_focused = false;
 							window_focus_lost = true;
+							basalt.send_event(BasaltEvent::LostFocus);
 							
 							for (_hook_id, (hook_data, hook_func)) in &hook_map {
 								if hook_data.ty() == InputHookTy::WindowLostFocus {
@@ -1048,8 +1106,22 @@ impl Input {
 						},
 						
 						Event::MouseScroll(v) => {
-							m_scroll_amt += v;
-							scrolled = true;
+							let shift = *key_state.entry(Qwery::LShift).or_insert(false)
+								|| *key_state.entry(Qwery::RShift).or_insert(false);
+							
+							// Shift + wheel scrolls horizontally.
+							if shift {
+								m_scroll_x_amt += v;
+								scrolled_x = true;
+							} else {
+								m_scroll_amt += v;
+								scrolled = true;
+							}
+						},
+						
+						Event::MouseScrollX(v) => {
+							m_scroll_x_amt += v;
+							scrolled_x = true;
 						},
 						
 						_ => unreachable!()
@@ -1099,6 +1171,19 @@ impl Input {
 							}
 						},
 						
+						InputHookData::MouseScrollX {
+							scroll_amt,
+							mouse_x,
+							mouse_y,
+						} => {
+							if scrolled_x && mouse_inside {
+								*scroll_amt = m_scroll_x_amt;
+								call = true;
+								*mouse_x = mouse_pos_x;
+								*mouse_y = mouse_pos_y;
+							}
+						},
+						
 						_ => ()
 					}
 					
@@ -1338,9 +1423,10 @@ impl Input {
 					continue;
 				}
 				
+				drop(basalt);
 				thread::sleep(Duration::from_millis(10) - start.elapsed());
 			}
-		});
+		}));
 		
 		input_ret
 	}
@@ -1,3 +1,5 @@
+use logging;
+
 #[derive(Debug,Copy,Clone,PartialEq,Eq,PartialOrd,Ord,Hash)]
 pub enum Qwery {
 	Esc, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, Tilda, One,
@@ -443,7 +445,7 @@ impl From<u32> for Qwery {
 						80 => Qwery::ArrowDown,
 						77 => Qwery::ArrowRight,
 						_ => {
-							println!("Qwery from ScanCode: Unsupported keycode: {}", code);
+							debug!(target: logging::INPUT, "Qwery from ScanCode: Unsupported keycode: {}", code);
 							Qwery::Esc
 						}
 					}	
@@ -465,7 +467,7 @@ impl From<u32> for Qwery {
 						108 => Qwery::ArrowDown,
 						106 => Qwery::ArrowRight,
 						_ => {
-							println!("Qwery from ScanCode: Unsupported keycode: {}", code);
+							debug!(target: logging::INPUT, "Qwery from ScanCode: Unsupported keycode: {}", code);
 							Qwery::Esc
 						}
 					}
@@ -1,9 +1,11 @@
 use Basalt;
+use BasaltEvent;
 use std::sync::Arc;
 use winit;
 use winit::WindowEvent;
 use winit::DeviceEvent;
 use super::*;
+use logging;
 
 pub const ENABLED: bool = true;
 
@@ -12,101 +14,156 @@ pub fn run(basalt: Arc<Basalt>, events_loop: &mut winit::EventsLoop) {
 		return;
 	}
 
+	let main_id = basalt.surface_ref().unwrap().window().id();
 	let mut mouse_inside = true;
+	let mut exit = false;
+	#[cfg(target_os = "windows")]
+	let mut pixel_delta_warned = false;
 	
-	events_loop.run_forever(|ev| {
-		match ev {
-			winit::Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
-				basalt.exit();
-				return winit::ControlFlow::Break;
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
-				let winit::dpi::PhysicalPosition { x, y }
-					= position.to_physical(basalt.surface.window().get_hidpi_factor());
-				basalt.input_ref().send_event(Event::MousePosition(x as f32, y as f32));
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, .. } => {
-				basalt.input_ref().send_event(match input.state {
-					winit::ElementState::Pressed => Event::KeyPress(Qwery::from(input.scancode)),
-					winit::ElementState::Released => Event::KeyRelease(Qwery::from(input.scancode)),
-				});
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::MouseInput { state, button, .. }, .. } => {
-				let button = match button {
-					winit::MouseButton::Left => MouseButton::Left,
-					winit::MouseButton::Right => MouseButton::Right,
-					winit::MouseButton::Middle => MouseButton::Middle,
-					_ => return winit::ControlFlow::Continue
-				};
-			
-				basalt.input_ref().send_event(match state {
-					winit::ElementState::Pressed => Event::MousePress(button),
-					winit::ElementState::Released => Event::MouseRelease(button),
-				});
-			},
-			
-			#[cfg(target_os = "windows")]
-			winit::Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } => {
-				if mouse_inside {
-					basalt.input_ref().send_event(match delta {
-						winit::MouseScrollDelta::LineDelta(_, y) => {
-							Event::MouseScroll(-y)
-						}, winit::MouseScrollDelta::PixelDelta(data) => {
-							println!("WARNING winit::MouseScrollDelta::PixelDelta is untested!");
-							Event::MouseScroll(data.y as f32)
+	// The loop is broken out of when woken up so that requested windows can be created.
+	while !exit && !basalt.wants_exit() {
+		events_loop.run_forever(|ev| {
+			if let winit::Event::WindowEvent { window_id, .. } = &ev {
+				if *window_id != main_id {
+					if let winit::Event::WindowEvent { window_id, event } = ev {
+						if let Some(window) = basalt.window_by_id(window_id) {
+							window.handle_event(event);
 						}
-					});
-				}
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::CursorEntered { .. }, .. } => {
-				mouse_inside = true;
-				basalt.input_ref().send_event(Event::MouseEnter);
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::CursorLeft { .. }, .. } => {
-				mouse_inside = false;
-				basalt.input_ref().send_event(Event::MouseLeave);
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::Resized { .. }, .. } => {
-				basalt.input_ref().send_event(Event::WindowResized);
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::HiDpiFactorChanged(dpi), .. } => {
-				basalt.input_ref().send_event(Event::WindowDPIChange(dpi as f32));
-			},
-			
-			winit::Event::WindowEvent { event: WindowEvent::Focused(focused), .. } => {
-				basalt.input_ref().send_event(match focused {
-					true => Event::WindowFocused,
-					false => Event::WindowLostFocus
-				});
-			},
-			
-			winit::Event::DeviceEvent { event: DeviceEvent::Motion { axis, value }, .. } => {
-				basalt.input_ref().send_event(match axis {
-					0 => Event::MouseMotion(-value as f32, 0.0),
-					1 => Event::MouseMotion(0.0, -value as f32),
-					
-					#[cfg(not(target_os = "windows"))]
-					3 => if mouse_inside {
-						Event::MouseScroll(value as f32)
-					} else {
-						return winit::ControlFlow::Continue;
-					},
+					}
 					
-					_ => return winit::ControlFlow::Continue
-				});
-			},
+					return winit::ControlFlow::Continue;
+				}
+			}
 			
-			_ => ()
-		}
-	
-		winit::ControlFlow::Continue
-	});
+			match ev {
+				winit::Event::Awakened => return winit::ControlFlow::Break,
+				
+				winit::Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+					basalt.send_event(BasaltEvent::CloseRequested);
+					basalt.exit();
+					exit = true;
+					return winit::ControlFlow::Break;
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
+					let winit::dpi::PhysicalPosition { x, y }
+						= position.to_physical(basalt.hidpi_factor());
+					basalt.input_ref().send_event(Event::MousePosition(x as f32, y as f32));
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, .. } => {
+					basalt.input_ref().send_event(match input.state {
+						winit::ElementState::Pressed => Event::KeyPress(Qwery::from(input.scancode)),
+						winit::ElementState::Released => Event::KeyRelease(Qwery::from(input.scancode)),
+					});
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::MouseInput { state, button, .. }, .. } => {
+					let button = match button {
+						winit::MouseButton::Left => MouseButton::Left,
+						winit::MouseButton::Right => MouseButton::Right,
+						winit::MouseButton::Middle => MouseButton::Middle,
+						_ => return winit::ControlFlow::Continue
+					};
+				
+					basalt.input_ref().send_event(match state {
+						winit::ElementState::Pressed => Event::MousePress(button),
+						winit::ElementState::Released => Event::MouseRelease(button),
+					});
+				},
+				
+				#[cfg(target_os = "windows")]
+				winit::Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } => {
+					if mouse_inside {
+						// winit reports line deltas with up and right as positive while the raw axes
+						// used on other platforms report down and right as positive, so their y is
+						// flipped. Pixel deltas are passed through as is.
+						let (x, y) = match delta {
+							winit::MouseScrollDelta::LineDelta(x, y) => (x, -y),
+							winit::MouseScrollDelta::PixelDelta(data) => {
+								if !pixel_delta_warned {
+									basalt.warn(logging::INPUT, String::from("winit::MouseScrollDelta::PixelDelta is untested!"));
+									pixel_delta_warned = true;
+								}
+								
+								(data.x as f32, data.y as f32)
+							}
+						};
+						
+						if y != 0.0 {
+							basalt.input_ref().send_event(Event::MouseScroll(y));
+						}
+						
+						if x != 0.0 {
+							basalt.input_ref().send_event(Event::MouseScrollX(x));
+						}
+					}
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::CursorEntered { .. }, .. } => {
+					mouse_inside = true;
+					basalt.input_ref().send_event(Event::MouseEnter);
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::CursorLeft { .. }, .. } => {
+					mouse_inside = false;
+					basalt.input_ref().send_event(Event::MouseLeave);
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::Resized { .. }, .. } => {
+					basalt.input_ref().send_event(Event::WindowResized);
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::HiDpiFactorChanged(dpi), .. } => {
+					basalt.input_ref().send_event(Event::WindowDPIChange(dpi as f32));
+				},
+				
+				winit::Event::WindowEvent { event: WindowEvent::Focused(focused), .. } => {
+					basalt.input_ref().send_event(match focused {
+						true => Event::WindowFocused,
+						false => Event::WindowLostFocus
+					});
+				},
+				
+				winit::Event::DeviceEvent { event: DeviceEvent::Motion { axis, value }, .. } => {
+					basalt.input_ref().send_event(match axis {
+						0 => Event::MouseMotion(-value as f32, 0.0),
+						1 => Event::MouseMotion(0.0, -value as f32),
+						
+						#[cfg(not(target_os = "windows"))]
+						2 => if mouse_inside {
+							Event::MouseScrollX(value as f32)
+						} else {
+							if let Some(window) = basalt.windows().into_iter().find(|w| w.mouse_inside()) {
+								window.send_scroll_x(value as f32);
+							}
+							
+							return winit::ControlFlow::Continue;
+						},
+						
+						#[cfg(not(target_os = "windows"))]
+						3 => if mouse_inside {
+							Event::MouseScroll(value as f32)
+						} else {
+							if let Some(window) = basalt.windows().into_iter().find(|w| w.mouse_inside()) {
+								window.send_scroll(value as f32);
+							}
+							
+							return winit::ControlFlow::Continue;
+						},
+						
+						_ => return winit::ControlFlow::Continue
+					});
+				},
+				
+				_ => ()
+			}
+		
+			winit::ControlFlow::Continue
+		});
+		
+		basalt.create_requested_windows(events_loop);
+		basalt.drop_closed_surfaces();
+	}
 }
 
@@ -1,5 +1,5 @@
 use std::sync::atomic::{self,AtomicBool};
-use super::interface::ItfVertInfo;
+use super::interface::{Interface,ItfVertInfo};
 use interface::interface::scale_verts;
 use parking_lot::{RwLock,Mutex};
 use std::sync::{Weak,Arc};
@@ -7,18 +7,21 @@ use Basalt;
 use vulkano;
 use vulkano::image::traits::ImageViewAccess;
 use super::super::atlas;
-use std::thread;
 use std::time::Duration;
 pub use interface::TextWrap;
 use std::sync::Barrier;
 use vulkano::image::immutable::ImmutableImage;
 use std::time::Instant;
 use misc;
+use error::{BasaltError,RenderError};
 use interface::TextAlign;
 use interface::WrapTy;
 use interface::hook::{BinHook,BinHookID,BinHookFn,BinHookData};
 use std::f32::consts::PI;
+use std::collections::BTreeMap;
 use input::*;
+use scheduler::{TaskID,TaskRes};
+use logging;
 
 pub trait KeepAlive { }
 impl KeepAlive for Arc<Bin> {}
@@ -39,6 +42,7 @@ pub struct BinStyle {
 	pub hidden: Option<bool>,
 	pub opacity: Option<f32>,
 	pub pass_events: Option<bool>,
+	pub cursor: Option<BinCursor>,
 	// Position from Edges
 	pub pos_from_t: Option<f32>,
 	pub pos_from_b: Option<f32>,
@@ -55,11 +59,38 @@ pub struct BinStyle {
 	pub width_pct: Option<f32>,
 	pub height: Option<f32>,
 	pub height_pct: Option<f32>,
-	// Margin
-	pub margin_t: Option<f32>, //|
-	pub margin_b: Option<f32>, //| Not Implemented
-	pub margin_l: Option<f32>, //|
-	pub margin_r: Option<f32>, //|
+	// Size Constraints, percentages are of the parent and the aspect ratio is width / height.
+	// Like css the minimum wins over the maximum and both win over the aspect ratio.
+	pub min_width: Option<f32>,
+	pub min_width_pct: Option<f32>,
+	pub max_width: Option<f32>,
+	pub max_width_pct: Option<f32>,
+	pub min_height: Option<f32>,
+	pub min_height_pct: Option<f32>,
+	pub max_height: Option<f32>,
+	pub max_height_pct: Option<f32>,
+	pub aspect_ratio: Option<f32>,
+	// Margin, space kept from the edges the bin is positioned from. Percent sizes
+	// also shrink by the margins so that percent based siblings keep their spacing.
+	pub margin_t: Option<f32>,
+	pub margin_b: Option<f32>,
+	pub margin_l: Option<f32>,
+	pub margin_r: Option<f32>,
+	// Flow, layout of the children that are positioned with PositionTy::Flow
+	pub flow_dir: Option<FlowDir>,
+	pub flow_wrap: Option<bool>,
+	pub flow_gap: Option<f32>,
+	pub flow_justify: Option<FlowJustify>,
+	pub flow_align: Option<FlowAlign>,
+	// Grid, tracks of the parent and the cell of children positioned with PositionTy::Grid
+	pub grid_columns: Option<Vec<GridTrack>>,
+	pub grid_rows: Option<Vec<GridTrack>>,
+	pub grid_column_gap: Option<f32>,
+	pub grid_row_gap: Option<f32>,
+	pub grid_column: Option<usize>,
+	pub grid_row: Option<usize>,
+	pub grid_column_span: Option<usize>,
+	pub grid_row_span: Option<usize>,
 	// Padding
 	pub pad_t: Option<f32>, //|
 	pub pad_b: Option<f32>, //| Text Only
@@ -67,9 +98,9 @@ pub struct BinStyle {
 	pub pad_r: Option<f32>, //|
 	// Scrolling
 	pub scroll_y: Option<f32>,
-	pub scroll_x: Option<f32>, // Not Implemented
+	pub scroll_x: Option<f32>,
 	pub overflow_y: Option<bool>,
-	pub overflow_x: Option<bool>, // Not Implemented
+	pub overflow_x: Option<bool>, // Defaults to true, use false to clip horizontally
 	// Border
 	pub border_size_t: Option<f32>,
 	pub border_size_b: Option<f32>,
@@ -124,6 +155,83 @@ impl ImageEffect {
 	}
 }
 
+/// Mouse cursor shown while the mouse is over a bin. Bins without a cursor set
+/// use the cursor of their nearest ancestor that has one.
+#[derive(Clone,Debug,PartialEq)]
+pub enum BinCursor {
+	Default,
+	Text,
+	Hand,
+	Crosshair,
+	Move,
+	Wait,
+	Help,
+	NotAllowed,
+	Grab,
+	Grabbing,
+	ResizeN,
+	ResizeE,
+	ResizeS,
+	ResizeW,
+	ResizeNE,
+	ResizeNW,
+	ResizeSE,
+	ResizeSW,
+	ResizeEW,
+	ResizeNS,
+	Hidden,
+	/// Image loaded from a path through the atlas. ``hotspot`` is the point within
+	/// the image that is placed at the mouse position.
+	Image {
+		path: String,
+		size: [f32; 2],
+		hotspot: [f32; 2],
+	},
+	/// Same as ``Image`` but the image is downloaded from a url.
+	ImageUrl {
+		url: String,
+		size: [f32; 2],
+		hotspot: [f32; 2],
+	},
+}
+
+impl Default for BinCursor {
+	fn default() -> Self {
+		BinCursor::Default
+	}
+}
+
+impl BinCursor {
+	/// The system cursor to use, ``None`` if the system cursor should be hidden.
+	pub(crate) fn winit_cursor(&self) -> Option<::winit::MouseCursor> {
+		Some(match self {
+			BinCursor::Default => ::winit::MouseCursor::Default,
+			BinCursor::Text => ::winit::MouseCursor::Text,
+			BinCursor::Hand => ::winit::MouseCursor::Hand,
+			BinCursor::Crosshair => ::winit::MouseCursor::Crosshair,
+			BinCursor::Move => ::winit::MouseCursor::Move,
+			BinCursor::Wait => ::winit::MouseCursor::Wait,
+			BinCursor::Help => ::winit::MouseCursor::Help,
+			BinCursor::NotAllowed => ::winit::MouseCursor::NotAllowed,
+			BinCursor::Grab => ::winit::MouseCursor::Grab,
+			BinCursor::Grabbing => ::winit::MouseCursor::Grabbing,
+			BinCursor::ResizeN => ::winit::MouseCursor::NResize,
+			BinCursor::ResizeE => ::winit::MouseCursor::EResize,
+			BinCursor::ResizeS => ::winit::MouseCursor::SResize,
+			BinCursor::ResizeW => ::winit::MouseCursor::WResize,
+			BinCursor::ResizeNE => ::winit::MouseCursor::NeResize,
+			BinCursor::ResizeNW => ::winit::MouseCursor::NwResize,
+			BinCursor::ResizeSE => ::winit::MouseCursor::SeResize,
+			BinCursor::ResizeSW => ::winit::MouseCursor::SwResize,
+			BinCursor::ResizeEW => ::winit::MouseCursor::EwResize,
+			BinCursor::ResizeNS => ::winit::MouseCursor::NsResize,
+			BinCursor::Hidden |
+			BinCursor::Image { .. } |
+			BinCursor::ImageUrl { .. } => return None
+		})
+	}
+}
+
 struct ImageInfo {
 	image: Option<Arc<ImageViewAccess + Send + Sync>>,
 	coords: atlas::Coords,
@@ -136,6 +244,7 @@ pub struct Bin {
 	verts: Mutex<Vec<(Vec<ItfVertInfo>, Option<Arc<vulkano::image::traits::ImageViewAccess + Send + Sync>>, u64)>>,
 	id: u64,
 	basalt: Arc<Basalt>,
+	interface: Arc<Interface>,
 	parent: Mutex<Option<Weak<Bin>>>,
 	children: Mutex<Vec<Weak<Bin>>>,
 	back_image: Mutex<Option<ImageInfo>>,
@@ -147,6 +256,13 @@ pub struct Bin {
 	last_update: Mutex<Instant>,
 	hook_ids: Mutex<Vec<BinHookID>>,
 	used_by_basalt: AtomicBool,
+	flow_cache: Mutex<Option<FlowCache>>,
+}
+
+/// Positions of the flow children relative to the parent for a given parent size.
+struct FlowCache {
+	size: [f32; 2],
+	rects: BTreeMap<u64, (f32, f32, f32, f32)>,
 }
 
 #[derive(Clone,Default)]
@@ -162,6 +278,8 @@ pub struct PostUpdate {
 	pub z_index: i16,
 	pub pre_bound_min_y: f32,
 	pub pre_bound_max_y: f32,
+	pub pre_bound_min_x: f32,
+	pub pre_bound_max_x: f32,
 }
 
 impl Drop for Bin {
@@ -170,12 +288,18 @@ impl Drop for Bin {
 			self.basalt.input_ref().remove_hook(hook);
 		}
 		
-		self.basalt.interface_ref().hook_manager.remove_hooks(self.hook_ids.lock().split_off(0));
+		self.interface.hook_manager.remove_hooks(self.hook_ids.lock().split_off(0));
+		
+		if is_flow(&*self.style.lock()) {
+			if let Some(parent) = self.parent() {
+				parent.flow_changed();
+			}
+		}
 	}
 }
 
 impl Bin {
-	pub(crate) fn new(id: u64, basalt: Arc<Basalt>) -> Arc<Self> {
+	pub(crate) fn new(id: u64, basalt: Arc<Basalt>, interface: Arc<Interface>) -> Arc<Self> {
 		Arc::new(Bin {
 			initial: Mutex::new(true),
 			style: Mutex::new(BinStyle::default()),
@@ -183,6 +307,7 @@ impl Bin {
 			verts: Mutex::new(Vec::new()),
 			id: id,
 			basalt: basalt.clone(),
+			interface,
 			parent: Mutex::new(None),
 			children: Mutex::new(Vec::new()),
 			back_image: Mutex::new(None),
@@ -194,6 +319,7 @@ impl Bin {
 			last_update: Mutex::new(Instant::now()),
 			hook_ids: Mutex::new(Vec::new()),
 			used_by_basalt: AtomicBool::new(false),
+			flow_cache: Mutex::new(None),
 		})
 	}
 	
@@ -219,14 +345,29 @@ impl Bin {
 		out
 	}
 	
+	/// The cursor used when the mouse is over this bin, taking ancestors into account.
+	pub fn cursor(&self) -> BinCursor {
+		if let Some(cursor) = self.style.lock().cursor.clone() {
+			return cursor;
+		}
+		
+		for ancestor in self.ancestors() {
+			if let Some(cursor) = ancestor.style.lock().cursor.clone() {
+				return cursor;
+			}
+		}
+		
+		BinCursor::Default
+	}
+	
 	pub fn add_hook_raw(self: &Arc<Self>, hook: BinHook, func: BinHookFn) -> BinHookID {
-		let id = self.basalt.interface_ref().hook_manager.add_hook(self.clone(), hook, func);
+		let id = self.interface.hook_manager.add_hook(self.clone(), hook, func);
 		self.hook_ids.lock().push(id);
 		id
 	}
 	
 	pub fn remove_hook(self: &Arc<Self>, hook_id: BinHookID) {
-		self.basalt.interface_ref().hook_manager.remove_hook(hook_id);
+		self.interface.hook_manager.remove_hook(hook_id);
 		let mut hook_ids = self.hook_ids.lock();
 		
 		for i in 0..hook_ids.len() {
@@ -238,7 +379,7 @@ impl Bin {
 	}
 	
 	pub fn on_key_press(self: &Arc<Self>, key: Qwery, func: BinHookFn) -> BinHookID {
-		let id = self.basalt.interface_ref().hook_manager.add_hook(self.clone(), BinHook::Press {
+		let id = self.interface.hook_manager.add_hook(self.clone(), BinHook::Press {
 			keys: vec![key],
 			mouse_buttons: Vec::new(),
 		}, func);
@@ -247,7 +388,7 @@ impl Bin {
 	}
 	
 	pub fn on_key_release(self: &Arc<Self>, key: Qwery, func: BinHookFn) -> BinHookID {
-		let id = self.basalt.interface_ref().hook_manager.add_hook(self.clone(), BinHook::Release {
+		let id = self.interface.hook_manager.add_hook(self.clone(), BinHook::Release {
 			keys: vec![key],
 			mouse_buttons: Vec::new(),
 		}, func);
@@ -256,7 +397,7 @@ impl Bin {
 	}
 	
 	pub fn on_key_hold(self: &Arc<Self>, key: Qwery, func: BinHookFn) -> BinHookID {
-		let id = self.basalt.interface_ref().hook_manager.add_hook(self.clone(), BinHook::Hold {
+		let id = self.interface.hook_manager.add_hook(self.clone(), BinHook::Hold {
 			keys: vec![key],
 			mouse_buttons: Vec::new(),
 			initial_delay: Duration::from_millis(1000),
@@ -268,7 +409,7 @@ impl Bin {
 	}
 	
 	pub fn on_mouse_press(self: &Arc<Self>, button: MouseButton, func: BinHookFn) -> BinHookID {
-		let id = self.basalt.interface_ref().hook_manager.add_hook(self.clone(), BinHook::Press {
+		let id = self.interface.hook_manager.add_hook(self.clone(), BinHook::Press {
 			keys: Vec::new(),
 			mouse_buttons: vec![button],
 		}, func);
@@ -277,7 +418,7 @@ impl Bin {
 	}
 	
 	pub fn on_mouse_release(self: &Arc<Self>, button: MouseButton, func: BinHookFn) -> BinHookID {
-		let id = self.basalt.interface_ref().hook_manager.add_hook(self.clone(), BinHook::Release {
+		let id = self.interface.hook_manager.add_hook(self.clone(), BinHook::Release {
 			keys: Vec::new(),
 			mouse_buttons: vec![button],
 		}, func);
@@ -286,7 +427,7 @@ impl Bin {
 	}
 	
 	pub fn on_mouse_hold(self: &Arc<Self>, button: MouseButton, func: BinHookFn) -> BinHookID {
-		let id = self.basalt.interface_ref().hook_manager.add_hook(self.clone(), BinHook::Hold {
+		let id = self.interface.hook_manager.add_hook(self.clone(), BinHook::Hold {
 			keys: Vec::new(),
 			mouse_buttons: vec![button],
 			initial_delay: Duration::from_millis(1000),
@@ -304,12 +445,23 @@ impl Bin {
 	pub fn add_child(self: &Arc<Self>, child: Arc<Bin>) {
 		*child.parent.lock() = Some(Arc::downgrade(self));
 		self.children.lock().push(Arc::downgrade(&child));
+		
+		if is_flow(&*child.style.lock()) {
+			self.flow_changed();
+		}
 	}
 	
 	pub fn add_children(self: &Arc<Self>, children: Vec<Arc<Bin>>) {
+		let mut flow = false;
+		
 		for child in children {
 			*child.parent.lock() = Some(Arc::downgrade(self));
 			self.children.lock().push(Arc::downgrade(&child));
+			flow |= is_flow(&*child.style.lock());
+		}
+		
+		if flow {
+			self.flow_changed();
 		}
 	}
 	
@@ -318,14 +470,20 @@ impl Bin {
 	}
 	
 	pub fn take_children(&self) -> Vec<Arc<Bin>> {
-		self.children.lock().split_off(0).into_iter().filter_map(|child_wk| {
+		let children: Vec<_> = self.children.lock().split_off(0).into_iter().filter_map(|child_wk| {
 			match child_wk.upgrade() {
 				Some(child) => {
 					*child.parent.lock() = None;
 					Some(child)
 				}, None => None
 			}
-		}).collect()
+		}).collect();
+		
+		if children.iter().any(|child| is_flow(&*child.style.lock())) {
+			self.flow_changed();
+		}
+		
+		children
 	}
 	
 	pub fn children(&self) -> Vec<Arc<Bin>> {
@@ -442,6 +600,29 @@ impl Bin {
 		})));
 	}
 	
+	/// Scroll this bin horizontally with ``MouseScrollX``, which includes Shift + wheel.
+	/// The content should be clipped with ``overflow_x: Some(false)``.
+	pub fn add_scroll_x_events(self: &Arc<Self>) {
+		self.add_hook_raw(BinHook::MouseScrollX, Arc::new(move |bin, data| {
+			if let BinHookData::MouseScrollX { scroll_amt, .. } = data {
+				let style = bin.style_copy();
+				let scroll_x = style.scroll_x.unwrap_or(0.0);
+				let to = (scroll_x + *scroll_amt).max(0.0).min(bin.calc_overflow_x());
+				
+				if to != scroll_x {
+					bin.style_update(BinStyle {
+						scroll_x: Some(to),
+						.. style
+					});
+					
+					bin.update_children();
+				}
+			}
+		}));
+	}
+	
+	/// Append typed characters to the text and remove the last one on backspace. Ctrl+C and
+	/// Ctrl+X copy or cut the whole text and Ctrl+V appends the clipboard's text.
 	pub fn add_enter_text_events(self: &Arc<Self>) {
 		self.add_hook_raw(BinHook::Character, Arc::new(move |bin, data| {
 			if let BinHookData::Character {
@@ -458,6 +639,45 @@ impl Bin {
 				bin.style_update(style);
 			}
 		}));
+		
+		// Text entry has no selection, so copy and cut apply to all of the text.
+		for &ctrl in &[Qwery::LCtrl, Qwery::RCtrl] {
+			self.add_hook_raw(BinHook::Press {
+				keys: vec![ctrl, Qwery::C],
+				mouse_buttons: Vec::new(),
+			}, Arc::new(move |bin, _| {
+				if let Err(e) = bin.basalt.clipboard_ref().set_text(bin.style_copy().text) {
+					bin.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, failed to copy text: {}", bin.id, e));
+				}
+			}));
+			
+			self.add_hook_raw(BinHook::Press {
+				keys: vec![ctrl, Qwery::X],
+				mouse_buttons: Vec::new(),
+			}, Arc::new(move |bin, _| {
+				let mut style = bin.style_copy();
+				
+				match bin.basalt.clipboard_ref().set_text(style.text.split_off(0)) {
+					Ok(_) => bin.style_update(style),
+					Err(e) => bin.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, failed to cut text: {}", bin.id, e))
+				}
+			}));
+			
+			self.add_hook_raw(BinHook::Press {
+				keys: vec![ctrl, Qwery::V],
+				mouse_buttons: Vec::new(),
+			}, Arc::new(move |bin, _| {
+				match bin.basalt.clipboard_ref().get_text() {
+					Ok(Some(text)) => {
+						let mut style = bin.style_copy();
+						style.text.push_str(&text);
+						bin.style_update(style);
+					},
+					Ok(None) => (),
+					Err(e) => bin.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, failed to paste text: {}", bin.id, e))
+				}
+			}));
+		}
 	}
 	
 	// TODO: Use Bin Hooks
@@ -512,58 +732,58 @@ impl Bin {
 		})));
 	}
 	
-	pub fn fade_out(self: &Arc<Self>, millis: u64) {
+	pub fn fade_out(self: &Arc<Self>, millis: u64) -> TaskID {
 		let bin = self.clone();
 		let start_opacity = self.style_copy().opacity.unwrap_or(1.0);
 		let steps = (millis/10) as i64;
 		let step_size = start_opacity / steps as f32;
-		let mut step_i = 0;
-	
-		thread::spawn(move || {
-			loop {
-				if step_i > steps {
-					break;
-				}
-				
-				let opacity = start_opacity - (step_i as f32 * step_size);
-				let mut copy = bin.style_copy();
-				copy.opacity = Some(opacity);
-				
-				if step_i == steps {
-					copy.hidden = Some(true);
-				}
-				
-				bin.style_update(copy);
-				bin.update_children();
-				step_i += 1;
-				thread::sleep(Duration::from_millis(10));
+		let step_i = Mutex::new(0);
+		
+		self.basalt.scheduler_ref().set_interval(Duration::from_millis(10), Arc::new(move || {
+			let mut step_i = step_i.lock();
+			let opacity = start_opacity - (*step_i as f32 * step_size);
+			let mut copy = bin.style_copy();
+			copy.opacity = Some(opacity);
+			
+			if *step_i == steps {
+				copy.hidden = Some(true);
 			}
-		});
+			
+			bin.style_update(copy);
+			bin.update_children();
+			*step_i += 1;
+			
+			if *step_i > steps {
+				TaskRes::Remove
+			} else {
+				TaskRes::Continue
+			}
+		}))
 	}
 	
-	pub fn fade_in(self: &Arc<Self>, millis: u64, target: f32) {
+	pub fn fade_in(self: &Arc<Self>, millis: u64, target: f32) -> TaskID {
 		let bin = self.clone();
 		let start_opacity = bin.style_copy().opacity.unwrap_or(1.0);
 		let steps = (millis/10) as i64;
 		let step_size = (target-start_opacity) / steps as f32;
-		let mut step_i = 0;
-	
-		thread::spawn(move || {
-			loop {
-				if step_i > steps {
-					break;
-				}
-				
-				let opacity = (step_i as f32 * step_size) + start_opacity;
-				let mut copy = bin.style_copy();
-				copy.opacity = Some(opacity);
-				copy.hidden = Some(false);
-				bin.style_update(copy);
-				bin.update_children();
-				step_i += 1;
-				thread::sleep(Duration::from_millis(10));
+		let step_i = Mutex::new(0);
+		
+		self.basalt.scheduler_ref().set_interval(Duration::from_millis(10), Arc::new(move || {
+			let mut step_i = step_i.lock();
+			let opacity = (*step_i as f32 * step_size) + start_opacity;
+			let mut copy = bin.style_copy();
+			copy.opacity = Some(opacity);
+			copy.hidden = Some(false);
+			bin.style_update(copy);
+			bin.update_children();
+			*step_i += 1;
+			
+			if *step_i > steps {
+				TaskRes::Remove
+			} else {
+				TaskRes::Continue
 			}
-		});
+		}))
 	}
 	
 	pub fn calc_overflow(self: &Arc<Bin>) -> f32 {
@@ -598,6 +818,38 @@ impl Bin {
 		}
 	}
 	
+	/// Horizontal counterpart of ``calc_overflow()``.
+	pub fn calc_overflow_x(self: &Arc<Bin>) -> f32 {
+		let mut min_x = 0.0;
+		let mut max_x = 0.0;
+		
+		for child in self.children() {
+			let post = child.post_update.read();
+			
+			if post.pre_bound_min_x < min_x {
+				min_x = post.pre_bound_min_x;
+			}
+			
+			if post.pre_bound_max_x > max_x {
+				max_x = post.pre_bound_max_x;
+			}
+		}
+		
+		let style = self.style.lock();
+		let pad_l = style.pad_l.clone().unwrap_or(0.0);
+		let pad_r = style.pad_r.clone().unwrap_or(0.0);
+		let content_width = max_x - min_x + pad_l + pad_r;
+		let self_post = self.post_update.read();
+		// Same as calc_overflow(), the bounds start at zero so the right edge is compared.
+		let width = self_post.tri[0];
+		
+		if content_width > width {
+			content_width - width
+		} else {
+			0.0
+		}
+	}
+	
 	pub fn on_update(&self, func: Arc<Fn() + Send + Sync>) {
 		self.on_update.lock().push(func);
 	}
@@ -621,6 +873,103 @@ impl Bin {
 		self.post_update.read().clone()
 	}
 	
+	/// Render this bin and its children into an image the size of this bin. Bins outside
+	/// of this tree are not included even if they overlap.
+	pub fn capture(self: &Arc<Bin>) -> Result<image::RgbaImage, BasaltError> {
+		use vulkano::buffer::{BufferUsage,BufferAccess,CpuAccessibleBuffer};
+		use vulkano::command_buffer::{AutoCommandBufferBuilder,CommandBuffer};
+		use vulkano::sync::GpuFuture;
+		use interface::render::{ItfRenderer,ItfDrawSet};
+		use misc::readback::Readback;
+		use decorum::R32;
+		use std::collections::BTreeMap;
+		
+		let win_size = self.interface.odb.win_size();
+		let scale = self.interface.scale();
+		let post = self.post_update();
+		let x = (post.tlo[0] * scale).floor().max(0.0) as u32;
+		let y = (post.tlo[1] * scale).floor().max(0.0) as u32;
+		let w = ((post.bro[0] * scale).ceil().min(win_size[0] as f32) as u32).saturating_sub(x);
+		let h = ((post.bro[1] * scale).ceil().min(win_size[1] as f32) as u32).saturating_sub(y);
+		
+		if w == 0 || h == 0 {
+			return Err(RenderError::EmptyCapture.into());
+		}
+		
+		// Ordered the same as the OrderedDualBuffer, furthest back first.
+		let mut mapped: BTreeMap<R32, Vec<(u64, Option<Arc<ImageViewAccess + Send + Sync>>, Vec<ItfVertInfo>)>> = BTreeMap::new();
+		
+		for bin in self.children_recursive() {
+			for (verts, image_op, atlas_id) in bin.verts_cp() {
+				for tri in verts.chunks_exact(3) {
+					let entry = mapped.entry(R32::from(-1.0 * tri[0].position.2)).or_insert_with(Vec::new);
+					
+					let existing = entry.iter_mut().find(|(a, i, _)| *a == atlas_id && match (i, &image_op) {
+						(None, None) => true,
+						(Some(a), Some(b)) => Arc::ptr_eq(a, b),
+						_ => false
+					});
+					
+					match existing {
+						Some((_, _, data)) => data.extend_from_slice(tri),
+						None => entry.push((atlas_id, image_op.clone(), tri.to_vec()))
+					}
+				}
+			}
+		}
+		
+		let sampler = self.basalt.atlas_ref().default_sampler();
+		let mut draw_sets: Vec<ItfDrawSet> = Vec::new();
+		
+		for (_, sets) in mapped {
+			for (atlas_id, image_op, data) in sets {
+				let img = match image_op {
+					Some(some) => some,
+					None => match self.interface.odb.atlas_image(atlas_id) {
+						Some(some) => some,
+						None => self.basalt.atlas_ref().empty_image()
+					}
+				};
+				
+				let buf = CpuAccessibleBuffer::from_iter(
+					self.basalt.device(),
+					BufferUsage {
+						vertex_buffer: true,
+						.. BufferUsage::none()
+					},
+					data.into_iter()
+				).map_err(|e| RenderError::CreateBuffer(e))?;
+				
+				draw_sets.push((buf as Arc<BufferAccess + Send + Sync>, img, sampler.clone()));
+			}
+		}
+		
+		let mut renderer_op = self.interface.capture_renderer.lock();
+		
+		if renderer_op.is_none() {
+			*renderer_op = Some(ItfRenderer::with_interface(self.basalt.clone(), self.interface.clone()));
+		}
+		
+		let renderer = renderer_op.as_mut().unwrap();
+		let cmd = AutoCommandBufferBuilder::primary_one_time_submit(self.basalt.device(), self.basalt.graphics_queue_ref().family())
+			.map_err(|e| RenderError::CreateCommandBuffer(e))?;
+		let (cmd, _) = renderer.draw_sets(cmd, win_size, draw_sets);
+		
+		let target = match renderer.target_image() {
+			Some(some) => some,
+			None => return Err(RenderError::NoTarget.into())
+		};
+		
+		let readback = Readback::new(&self.basalt, &target)?;
+		let cmd = readback.record(cmd, target)?.build().map_err(|e| RenderError::BuildCommandBuffer(e))?;
+		
+		cmd.execute(self.basalt.graphics_queue()).map_err(|e| RenderError::Execute(e))?
+			.then_signal_fence_and_flush().map_err(|e| RenderError::Flush(e))?
+			.wait(None).map_err(|e| RenderError::Flush(e))?;
+		
+		Ok(image::imageops::crop(&mut readback.read()?, x, y, w, h).to_image())
+	}
+	
 	pub fn id(&self) -> u64 {
 		self.id
 	}
@@ -645,9 +994,16 @@ impl Bin {
 	fn pos_size_tlwh(&self, win_size_: Option<[f32; 2]>) -> (f32, f32, f32, f32) {
 		let win_size = win_size_.unwrap_or([0.0, 0.0]);
 		let style = self.style_copy();
+		
+		match style.position_t {
+			Some(PositionTy::Flow) => return self.flow_tlwh(&style, win_size_),
+			Some(PositionTy::Grid) => return self.grid_tlwh(&style, win_size_),
+			_ => ()
+		}
+		
 		let (par_t, par_b, par_l, par_r) = match style.position_t.unwrap_or(PositionTy::FromWindow) {
 			PositionTy::FromWindow => (0.0, win_size[1], 0.0, win_size[0]),
-			PositionTy::FromParent => match self.parent() {
+			PositionTy::FromParent | PositionTy::Flow | PositionTy::Grid => match self.parent() {
 				Some(ref parent) => {
 					let (top, left, width, height) = parent.pos_size_tlwh(win_size_);
 					(top, top+height, left, left+width)
@@ -679,81 +1035,268 @@ impl Bin {
 			}
 		};
 		
-		let from_t = match pos_from_t {
-			Some(from_t) => par_t+from_t,
+		let margin_t = style.margin_t.unwrap_or(0.0);
+		let margin_b = style.margin_b.unwrap_or(0.0);
+		let margin_l = style.margin_l.unwrap_or(0.0);
+		let margin_r = style.margin_r.unwrap_or(0.0);
+		
+		let offset_t = style.pos_from_t_offset.unwrap_or(0.0);
+		let offset_l = style.pos_from_l_offset.unwrap_or(0.0);
+		let edge_t = pos_from_t.map(|from_t| par_t + from_t + margin_t + offset_t);
+		let edge_l = pos_from_l.map(|from_l| from_l + par_l + margin_l + offset_l);
+		
+		let width = match (edge_l, pos_from_r) {
+			(Some(edge_l), Some(from_r)) => Some(par_r - from_r - margin_r - edge_l),
+			_ => match style.width {
+				Some(some) => Some(some),
+				None => style.width_pct.map(|pct| (((pct / 100.0) * (par_r - par_l)) - margin_l - margin_r).max(0.0))
+			}
+		};
+		
+		let height = match (edge_t, pos_from_b) {
+			(Some(edge_t), Some(from_b)) => Some(par_b - from_b - margin_b - edge_t),
+			_ => match style.height {
+				Some(some) => Some(some),
+				None => style.height_pct.map(|pct| (((pct / 100.0) * (par_b - par_t)) - margin_t - margin_b).max(0.0))
+			}
+		};
+		
+		let (width, height) = constrain_size(&style, width, height, par_r - par_l, par_b - par_t);
+		
+		let width = match width {
+			Some(some) => some,
+			None => {
+				self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get width. Width \
+					must be provided or both position from left and right \
+					must be provided.", self.id
+				)); 0.0
+			}
+		};
+		
+		let height = match height {
+			Some(some) => some,
+			None => {
+				self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get height. Height \
+					must be provied or both position from top and bottom \
+					must be provied.", self.id
+				)); 0.0
+			}
+		};
+		
+		let from_t = match edge_t {
+			Some(some) => some,
 			None => match pos_from_b {
-				Some(from_b) => match style.height {
-					Some(height) => par_b - from_b - height,
-					None => {
-						println!("UI Bin Warning! ID: {}, Unable to get position \
-							from top, position from bottom is specified \
-							but no height was provied.", self.id
-						); 0.0
-					}
-				}, None => {
-					println!("UI Bin Warning! ID: {}, Unable to get position \
+				Some(from_b) => par_b - from_b - height - margin_b + offset_t,
+				None => {
+					self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get position \
 						from top, position from bottom is non specified.", self.id
-					); 0.0
+					)); offset_t
 				}
 			}
-		} + style.pos_from_t_offset.unwrap_or(0.0);
+		};
 		
-		let from_l = match pos_from_l {
-			Some(from_l) => from_l+par_l,
+		let from_l = match edge_l {
+			Some(some) => some,
 			None => match pos_from_r {
-				Some(from_r) => match style.width {
-					Some(width) => par_r - from_r - width,
-					None => {
-						println!("UI Bin Warning! ID: {}, Unable to get position \
-							from left, position from right is specified \
-							but no width was provided.", self.id
-						); 0.0
-					}
-				}, None => {
-					println!("UI Bin Warning! ID: {}, Unable to get position from\
+				Some(from_r) => par_r - from_r - width - margin_r + offset_l,
+				None => {
+					self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get position from\
 						left, position from right is not specified.", self.id
-					); 0.0
+					)); offset_l
 				}
 			}
-		} + style.pos_from_l_offset.unwrap_or(0.0);
+		};
 		
-		let width = {
-			if pos_from_l.is_some() && pos_from_r.is_some() {
-				par_r - pos_from_r.unwrap() - from_l
+		(from_t, from_l, width, height)
+	}
+	
+	/// Position and size of a bin positioned with ``PositionTy::Flow``. The flow siblings are
+	/// laid out together by the parent, see ``flow_rect``.
+	fn flow_tlwh(&self, style: &BinStyle, win_size_: Option<[f32; 2]>) -> (f32, f32, f32, f32) {
+		let parent = match self.parent() {
+			Some(some) => some,
+			None => {
+				self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get position, \
+					flow position requires a parent.", self.id
+				));
+				return (0.0, 0.0, 0.0, 0.0);
+			}
+		};
+		
+		let (par_t, par_l, par_w, par_h) = parent.pos_size_tlwh(win_size_);
+		
+		match parent.flow_rect(self.id, par_w, par_h) {
+			Some((from_t, from_l, width, height)) => (
+				par_t + from_t + style.pos_from_t_offset.unwrap_or(0.0),
+				par_l + from_l + style.pos_from_l_offset.unwrap_or(0.0),
+				width,
+				height
+			),
+			// Hidden, so it doesn't take up any space.
+			None => (par_t, par_l, 0.0, 0.0)
+		}
+	}
+	
+	/// Position and size of a flow child relative to this bin. The whole run is laid out
+	/// once and kept until the size of this bin changes or ``flow_changed`` is called.
+	fn flow_rect(&self, child_id: u64, par_w: f32, par_h: f32) -> Option<(f32, f32, f32, f32)> {
+		let mut cache = self.flow_cache.lock();
+		
+		if let Some(ref cached) = *cache {
+			if cached.size == [par_w, par_h] {
+				return cached.rects.get(&child_id).cloned();
+			}
+		}
+		
+		let rects = self.flow_layout(par_w, par_h);
+		let rect = rects.get(&child_id).cloned();
+		*cache = Some(FlowCache { size: [par_w, par_h], rects });
+		rect
+	}
+	
+	fn flow_layout(&self, par_w: f32, par_h: f32) -> BTreeMap<u64, (f32, f32, f32, f32)> {
+		let style = self.style_copy();
+		let row = style.flow_dir.unwrap_or(FlowDir::Row) == FlowDir::Row;
+		let wrap = style.flow_wrap.unwrap_or(false);
+		let gap = style.flow_gap.unwrap_or(0.0);
+		let justify = style.flow_justify.unwrap_or(FlowJustify::Start);
+		let align = style.flow_align.unwrap_or(FlowAlign::Start);
+		let (main_avail, cross_avail) = if row { (par_w, par_h) } else { (par_h, par_w) };
+		let mut ids = Vec::new();
+		let mut items = Vec::new();
+		
+		for child in self.children() {
+			let child_style = child.style_copy();
+			
+			if !is_flow(&child_style) || child_style.hidden.unwrap_or(false) {
+				continue;
+			}
+			
+			let margin_t = child_style.margin_t.unwrap_or(0.0);
+			let margin_b = child_style.margin_b.unwrap_or(0.0);
+			let margin_l = child_style.margin_l.unwrap_or(0.0);
+			let margin_r = child_style.margin_r.unwrap_or(0.0);
+			
+			let width = match child_style.width {
+				Some(some) => Some(some),
+				None => child_style.width_pct.map(|pct| (((pct / 100.0) * par_w) - margin_l - margin_r).max(0.0))
+			};
+			
+			let height = match child_style.height {
+				Some(some) => Some(some),
+				None => child_style.height_pct.map(|pct| (((pct / 100.0) * par_h) - margin_t - margin_b).max(0.0))
+			};
+			
+			let (width, height) = constrain_size(&child_style, width, height, par_w, par_h);
+			
+			let (main, cross, margin_main, margin_cross) = if row {
+				(width, height, (margin_l, margin_r), (margin_t, margin_b))
 			} else {
-				match style.width {
-					Some(some) => some,
-					None => match style.width_pct {
-						Some(some) => (some / 100.0) * (par_r - par_l),
-						None => {
-							println!("UI Bin Warning! ID: {}, Unable to get width. Width \
-								must be provided or both position from left and right \
-								must be provided.", self.id
-							); 0.0
-						}
-					}
-				}
+				(height, width, (margin_t, margin_b), (margin_l, margin_r))
+			};
+			
+			if main.is_none() {
+				self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get {}. \
+					Flow children must provide their size in the flow direction.",
+					child.id, if row { "width" } else { "height" }
+				));
+			}
+			
+			if cross.is_none() && align != FlowAlign::Stretch {
+				self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get {}. \
+					Flow children must provide their size across the flow direction \
+					unless the parent aligns with stretch.",
+					child.id, if row { "height" } else { "width" }
+				));
 			}
-		}; let height = {
-			if pos_from_t.is_some() && pos_from_b.is_some() {
-				par_b - pos_from_b.unwrap() - from_t
+			
+			ids.push(child.id);
+			
+			items.push(FlowItem {
+				main: main.unwrap_or(0.0),
+				cross,
+				margin_main,
+				margin_cross,
+			});
+		}
+		
+		flow_run(&items, main_avail, cross_avail, wrap, gap, &justify, &align).into_iter().zip(ids).map(
+			|((main_pos, cross_pos, main, cross), id)| if row {
+				(id, (cross_pos, main_pos, main, cross))
 			} else {
-				match style.height {
-					Some(some) => some,
-					None => match style.height_pct {
-						Some(some) => (some / 100.0) * (par_b - par_t),
-						None => {
-							println!("UI Bin Warning! ID: {}, Unable to get height. Height \
-								must be provied or both position from top and bottom \
-								must be provied.", self.id
-							); 0.0
-						}
-					}
-				}
+				(id, (main_pos, cross_pos, cross, main))
 			}
-		}; 
+		).collect()
+	}
+	
+	/// Drops the cached flow layout and moves the flow children to their new positions.
+	fn flow_changed(&self) {
+		*self.flow_cache.lock() = None;
+		self.update_children();
+		self.interface.odb.unpark();
+	}
+	
+	/// Position and size of a bin positioned with ``PositionTy::Grid``.
+	fn grid_tlwh(&self, style: &BinStyle, win_size_: Option<[f32; 2]>) -> (f32, f32, f32, f32) {
+		let parent = match self.parent() {
+			Some(some) => some,
+			None => {
+				self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Unable to get position, \
+					grid position requires a parent.", self.id
+				));
+				return (0.0, 0.0, 0.0, 0.0);
+			}
+		};
 		
-		(from_t, from_l, width, height)
+		let (par_t, par_l, par_w, par_h) = parent.pos_size_tlwh(win_size_);
+		let par_style = parent.style_copy();
+		let columns = grid_tracks(par_style.grid_columns.as_ref(), par_w, par_style.grid_column_gap.unwrap_or(0.0));
+		let rows = grid_tracks(par_style.grid_rows.as_ref(), par_h, par_style.grid_row_gap.unwrap_or(0.0));
+		
+		let cell = |tracks: &Vec<(f32, f32)>, index: usize, span: usize, what: &str| -> (f32, f32) {
+			if index >= tracks.len() {
+				self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Grid {} {} is out of \
+					bounds, the parent only has {}.", self.id, what, index, tracks.len()
+				));
+				return (0.0, 0.0);
+			}
+			
+			let last = (index + span.max(1)).min(tracks.len()) - 1;
+			let start = tracks[index].0;
+			(start, tracks[last].0 + tracks[last].1 - start)
+		};
+		
+		let (cell_l, cell_w) = cell(&columns, style.grid_column.unwrap_or(0), style.grid_column_span.unwrap_or(1), "column");
+		let (cell_t, cell_h) = cell(&rows, style.grid_row.unwrap_or(0), style.grid_row_span.unwrap_or(1), "row");
+		let margin_t = style.margin_t.unwrap_or(0.0);
+		let margin_b = style.margin_b.unwrap_or(0.0);
+		let margin_l = style.margin_l.unwrap_or(0.0);
+		let margin_r = style.margin_r.unwrap_or(0.0);
+		
+		let width = match style.width {
+			Some(some) => some,
+			None => match style.width_pct {
+				Some(some) => (((some / 100.0) * cell_w) - margin_l - margin_r).max(0.0),
+				None => (cell_w - margin_l - margin_r).max(0.0)
+			}
+		};
+		
+		let height = match style.height {
+			Some(some) => some,
+			None => match style.height_pct {
+				Some(some) => (((some / 100.0) * cell_h) - margin_t - margin_b).max(0.0),
+				None => (cell_h - margin_t - margin_b).max(0.0)
+			}
+		};
+		
+		let (width, height) = constrain_known_size(style, width, height, cell_w, cell_h);
+		
+		(
+			par_t + cell_t + margin_t + style.pos_from_t_offset.unwrap_or(0.0),
+			par_l + cell_l + margin_l + style.pos_from_l_offset.unwrap_or(0.0),
+			width,
+			height
+		)
 	}
 	
 	pub fn visible(&self) -> bool {
@@ -858,7 +1401,7 @@ impl Bin {
 		if self.used_by_basalt.load(atomic::Ordering::Relaxed) {
 			z_index += ::std::i16::MAX - 100;
 		} else if z_index >= ::std::i16::MAX - 100 {
-			println!("Max z-index of {} reached!", ::std::i16::MAX - 101);
+			self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, Max z-index of {} reached!", self.id, ::std::i16::MAX - 101));
 			z_index = ::std::i16::MAX - 101;
 		}
 		
@@ -876,6 +1419,8 @@ impl Bin {
 			z_index: z_index,
 			pre_bound_min_y: 0.0,
 			pre_bound_max_y: 0.0,
+			pre_bound_min_x: 0.0,
+			pre_bound_max_x: 0.0,
 		};
 		
 		// -- Background Image --------------------------------------------------------- //
@@ -888,14 +1433,14 @@ impl Bin {
 				Some(path) => match self.basalt.atlas_ref().load_image_from_path(&path) {
 					Ok(coords) => (None, coords),
 					Err(e) => {
-						println!("UI Bin Warning! ID: {}, failed to load image into atlas {}: {}", self.id, path, e);
+						self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, failed to load image into atlas {}: {}", self.id, path, e));
 						(None, atlas::Coords::none())
 					}
 				}, None => match style.back_image_url {
 					Some(url) => match self.basalt.atlas_ref().load_image_from_url(&url) {
 						Ok(coords) => (None, coords),
 						Err(e) => {
-							println!("UI Bin Warning! ID: {}, failed to load image into atlas {}: {}", self.id, url, e);
+							self.basalt.warn(logging::INTERFACE, format!("UI Bin Warning! ID: {}, failed to load image into atlas {}: {}", self.id, url, e));
 							(None, atlas::Coords::none())
 						}
 					}, None => (None, atlas::Coords::none())
@@ -1225,7 +1770,7 @@ impl Bin {
 			),
 		};	
 		
-		match self.basalt.interface_ref().text_ref().render_text(
+		match self.interface.text_ref().render_text(
 			text, "default",
 			(text_size as f32 * scale).ceil() as u32,
 			text_color.as_tuple(),
@@ -1244,14 +1789,22 @@ impl Bin {
 					vert_data.push((verts, None, atlas_i));
 				}
 			}, Err(e) => {
-				println!("Failed to render text: {}", e);
+				self.basalt.warn(logging::TEXT, format!("UI Bin Warning! ID: {}, Failed to render text: {}", self.id, e));
 			}
 		}
 		
-		// -- Get current content height before overflow checks ------------------------ //
+		// -- Get current content size before overflow checks -------------------------- //
 		
 		for (verts, _, _) in &mut vert_data {
 			for vert in verts {
+				if vert.position.0 < bps.pre_bound_min_x {
+					bps.pre_bound_min_x = vert.position.0;
+				}
+				
+				if vert.position.0 > bps.pre_bound_max_x {
+					bps.pre_bound_max_x = vert.position.0;
+				}
+				
 				if vert.position.1 < bps.pre_bound_min_y {
 					bps.pre_bound_min_y = vert.position.1;
 				}
@@ -1263,7 +1816,6 @@ impl Bin {
 		}
 		
 		// -- Make sure that the verts are within the boundries of all ancestors. ------ //
-		// TODO: Implement horizonal checks
 		
 		let mut cut_amt;
 		let mut cut_percent;
@@ -1273,11 +1825,20 @@ impl Bin {
 		let mut coords_max_y;
 		let mut tri_h;
 		let mut img_h;
-		
-		for (_check_bin, check_style, check_pft, _check_pfl, _check_w, check_h) in &ancestor_data {
+		let mut pos_min_x;
+		let mut pos_max_x;
+		let mut coords_min_x;
+		let mut coords_max_x;
+		let mut tri_w;
+		let mut img_w;
+		
+		for (_check_bin, check_style, check_pft, check_pfl, check_w, check_h) in &ancestor_data {
 			let scroll_y = check_style.scroll_y.clone().unwrap_or(0.0);
 			let overflow_y = check_style.overflow_y.clone().unwrap_or(false);
+			let scroll_x = check_style.scroll_x.clone().unwrap_or(0.0);
+			let overflow_x = check_style.overflow_x.clone().unwrap_or(true);
 			let check_b = *check_pft + *check_h;
+			let check_r = *check_pfl + *check_w;
 			
 			if !overflow_y {	
 				let bps_check_y: Vec<&mut f32> = vec![
@@ -1298,6 +1859,25 @@ impl Bin {
 				}
 			}
 			
+			if !overflow_x {
+				let bps_check_x: Vec<&mut f32> = vec![
+					&mut bps.tli[0], &mut bps.tri[0],
+					&mut bps.bli[0], &mut bps.bri[0],
+					&mut bps.tlo[0], &mut bps.tro[0],
+					&mut bps.blo[0], &mut bps.bro[0]
+				];
+				
+				for x in bps_check_x {
+					*x -= scroll_x;
+					
+					if *x < *check_pfl {
+						*x = *check_pfl;
+					} else if *x > check_r {
+						*x = check_r;
+					}
+				}
+			}
+			
 			for (verts, _, _) in &mut vert_data {
 				let mut rm_tris: Vec<usize> = Vec::new();
 			
@@ -1305,40 +1885,81 @@ impl Bin {
 					tri[0].position.1 -= scroll_y;
 					tri[1].position.1 -= scroll_y;
 					tri[2].position.1 -= scroll_y;
+					tri[0].position.0 -= scroll_x;
+					tri[1].position.0 -= scroll_x;
+					tri[2].position.0 -= scroll_x;
+					
+					let outside_y = !overflow_y && (
+						(
+							tri[0].position.1 < *check_pft &&
+							tri[1].position.1 < *check_pft &&
+							tri[2].position.1 < *check_pft
+						) || (
+							tri[0].position.1 > check_b &&
+							tri[1].position.1 > check_b &&
+							tri[2].position.1 > check_b
+						)
+					);
+					
+					let outside_x = !overflow_x && (
+						(
+							tri[0].position.0 < *check_pfl &&
+							tri[1].position.0 < *check_pfl &&
+							tri[2].position.0 < *check_pfl
+						) || (
+							tri[0].position.0 > check_r &&
+							tri[1].position.0 > check_r &&
+							tri[2].position.0 > check_r
+						)
+					);
+					
+					if outside_y || outside_x {
+						rm_tris.push(tri_i);
+						continue;
+					}
 					
 					if !overflow_y {
-						if
-							(
-								tri[0].position.1 < *check_pft &&
-								tri[1].position.1 < *check_pft &&
-								tri[2].position.1 < *check_pft
-							) || (
-								tri[0].position.1 > check_b &&
-								tri[1].position.1 > check_b &&
-								tri[2].position.1 > check_b
-							)
-						{
-							rm_tris.push(tri_i);
-						} else {
-							pos_min_y = misc::partial_ord_min3(tri[0].position.1, tri[1].position.1, tri[2].position.1);
-							pos_max_y = misc::partial_ord_max3(tri[0].position.1, tri[1].position.1, tri[2].position.1);
-							coords_min_y = misc::partial_ord_min3(tri[0].coords.1, tri[1].coords.1, tri[2].coords.1);
-							coords_max_y = misc::partial_ord_max3(tri[0].coords.1, tri[1].coords.1, tri[2].coords.1);
-							tri_h = pos_max_y - pos_min_y;
-							img_h = coords_max_y - coords_min_y;
-							
-							for vert in tri {
-								if vert.position.1 < *check_pft {
-									cut_amt = check_pft - vert.position.1;
-									cut_percent = cut_amt / tri_h;
-									vert.coords.1 += cut_percent * img_h;
-									vert.position.1 += cut_amt;
-								} else if vert.position.1 > check_b {
-									cut_amt = vert.position.1 - check_b;
-									cut_percent = cut_amt / tri_h;
-									vert.coords.1 -= cut_percent * img_h;
-									vert.position.1 -= cut_amt;
-								}
+						pos_min_y = misc::partial_ord_min3(tri[0].position.1, tri[1].position.1, tri[2].position.1);
+						pos_max_y = misc::partial_ord_max3(tri[0].position.1, tri[1].position.1, tri[2].position.1);
+						coords_min_y = misc::partial_ord_min3(tri[0].coords.1, tri[1].coords.1, tri[2].coords.1);
+						coords_max_y = misc::partial_ord_max3(tri[0].coords.1, tri[1].coords.1, tri[2].coords.1);
+						tri_h = pos_max_y - pos_min_y;
+						img_h = coords_max_y - coords_min_y;
+						
+						for vert in tri.iter_mut() {
+							if vert.position.1 < *check_pft {
+								cut_amt = check_pft - vert.position.1;
+								cut_percent = cut_amt / tri_h;
+								vert.coords.1 += cut_percent * img_h;
+								vert.position.1 += cut_amt;
+							} else if vert.position.1 > check_b {
+								cut_amt = vert.position.1 - check_b;
+								cut_percent = cut_amt / tri_h;
+								vert.coords.1 -= cut_percent * img_h;
+								vert.position.1 -= cut_amt;
+							}
+						}
+					}
+					
+					if !overflow_x {
+						pos_min_x = misc::partial_ord_min3(tri[0].position.0, tri[1].position.0, tri[2].position.0);
+						pos_max_x = misc::partial_ord_max3(tri[0].position.0, tri[1].position.0, tri[2].position.0);
+						coords_min_x = misc::partial_ord_min3(tri[0].coords.0, tri[1].coords.0, tri[2].coords.0);
+						coords_max_x = misc::partial_ord_max3(tri[0].coords.0, tri[1].coords.0, tri[2].coords.0);
+						tri_w = pos_max_x - pos_min_x;
+						img_w = coords_max_x - coords_min_x;
+						
+						for vert in tri.iter_mut() {
+							if vert.position.0 < *check_pfl {
+								cut_amt = check_pfl - vert.position.0;
+								cut_percent = cut_amt / tri_w;
+								vert.coords.0 += cut_percent * img_w;
+								vert.position.0 += cut_amt;
+							} else if vert.position.0 > check_r {
+								cut_amt = vert.position.0 - check_r;
+								cut_percent = cut_amt / tri_w;
+								vert.coords.0 -= cut_percent * img_w;
+								vert.position.0 -= cut_amt;
 							}
 						}
 					}
@@ -1352,10 +1973,6 @@ impl Bin {
 			}
 		}
 		
-		/*if bps.pre_bound_max_y - bps.pre_bound_min_y > bps.bli[1] - bps.tli[1] {
-			println!("{} {}", bps.pre_bound_min_y, bps.pre_bound_max_y);
-		}*/
-		
 		// ----------------------------------------------------------------------------- //
 		
 		for &mut (ref mut verts, _, _) in &mut vert_data {
@@ -1376,7 +1993,7 @@ impl Bin {
 	
 	pub fn force_update(&self) {
 		self.update.store(true, atomic::Ordering::SeqCst);
-		self.basalt.interface_ref().odb.unpark();
+		self.interface.odb.unpark();
 	}
 	
 	pub fn style_copy(&self) -> BinStyle {
@@ -1384,10 +2001,22 @@ impl Bin {
 	}
 	
 	pub fn style_update(&self, copy: BinStyle) {
+		let flow = is_flow(&copy);
+		let was_flow = is_flow(&*self.style.lock());
 		*self.style.lock() = copy;
 		*self.initial.lock() = false;
 		self.update.store(true, atomic::Ordering::SeqCst);
-		self.basalt.interface_ref().odb.unpark();
+		// The flow options of this bin may have changed.
+		*self.flow_cache.lock() = None;
+		
+		// Changes to a flow child, including leaving the flow, move its siblings.
+		if flow || was_flow {
+			if let Some(parent) = self.parent() {
+				parent.flow_changed();
+			}
+		}
+		
+		self.interface.odb.unpark();
 	}
 	
 	pub fn update_children(&self) {
@@ -1424,10 +2053,10 @@ impl Bin {
 		});
 		
 		self.update.store(true, atomic::Ordering::SeqCst);
-		self.basalt.interface_ref().odb.unpark();
+		self.interface.odb.unpark();
 	}
 	
-	pub fn set_raw_img_yuv_422(&self, width: u32, height: u32, data: Vec<u8>) -> Result<(), String> {
+	pub fn set_raw_img_yuv_422(&self, width: u32, height: u32, data: Vec<u8>) -> Result<(), BasaltError> {
 		use vulkano::sync::GpuFuture;
 		
 		let mut back_image = self.back_image.lock();
@@ -1439,10 +2068,10 @@ impl Bin {
 				height: height + (height / 2),
 			}, vulkano::format::Format::R8Unorm,
 			self.basalt.transfer_queue()
-		).unwrap();
+		).map_err(|e| RenderError::CreateImage(e))?;
 		
-		let fence = future.then_signal_fence_and_flush().unwrap();
-		fence.wait(None).unwrap();
+		let fence = future.then_signal_fence_and_flush().map_err(|e| RenderError::Flush(e))?;
+		fence.wait(None).map_err(|e| RenderError::Flush(e))?;
 		
 		let mut coords = atlas::Coords::none();
 		coords.w = 1;
@@ -1454,11 +2083,11 @@ impl Bin {
 		});
 		
 		self.update.store(true, atomic::Ordering::SeqCst);
-		self.basalt.interface_ref().odb.unpark();
+		self.interface.odb.unpark();
 		Ok(())
 	}	
 	
-	pub fn separate_raw_image(&self, width: u32, height: u32, data: Vec<u8>) -> Result<(), String> {
+	pub fn separate_raw_image(&self, width: u32, height: u32, data: Vec<u8>) -> Result<(), BasaltError> {
 		let img = ImmutableImage::from_iter(
 			data.into_iter(),
 			vulkano::image::Dimensions::Dim2d {
@@ -1466,7 +2095,7 @@ impl Bin {
 				height: height,
 			}, vulkano::format::Format::R8G8B8A8Unorm,
 			self.basalt.graphics_queue()
-		).unwrap().0;
+		).map_err(|e| RenderError::CreateImage(e))?.0;
 		
 		let mut coords = atlas::Coords::none();
 		coords.w = 1;
@@ -1478,7 +2107,7 @@ impl Bin {
 		});
 		
 		self.update.store(true, atomic::Ordering::SeqCst);
-		self.basalt.interface_ref().odb.unpark();
+		self.interface.odb.unpark();
 		Ok(())
 	}
 	
@@ -1496,14 +2125,14 @@ impl Bin {
 		});
 		
 		self.update.store(true, atomic::Ordering::SeqCst);
-		self.basalt.interface_ref().odb.unpark();
+		self.interface.odb.unpark();
 		Ok(())
 	}*/
 	
 	pub fn remove_raw_back_img(&self) {
 		*self.back_image.lock() = None;
 		self.update.store(true, atomic::Ordering::SeqCst);
-		self.basalt.interface_ref().odb.unpark();
+		self.interface.odb.unpark();
 	}
 }
 
@@ -1511,6 +2140,300 @@ impl Bin {
 pub enum PositionTy {
 	FromWindow,
 	FromParent,
+	/// Placed after the previous flow siblings as set by the parent's ``flow_*`` style.
+	/// Only the size, margins and offsets of the bin are used.
+	Flow,
+	/// Placed in the cell of the parent's grid set by ``grid_column`` and ``grid_row``. The
+	/// cell is filled unless a width or height is provided, margins and offsets still apply.
+	Grid,
+}
+
+/// Size of a row or column in a grid.
+#[derive(Clone,Debug,PartialEq)]
+pub enum GridTrack {
+	Fixed(f32),
+	/// Percent of the parent's width for columns or height for rows.
+	Percent(f32),
+	/// Share of the space left after fixed and percent tracks and gaps.
+	Fraction(f32),
+}
+
+/// Min and max sizes of a style with the percentages resolved.
+struct SizeBounds {
+	min_w: Option<f32>,
+	max_w: Option<f32>,
+	min_h: Option<f32>,
+	max_h: Option<f32>,
+}
+
+impl SizeBounds {
+	fn new(style: &BinStyle, par_w: f32, par_h: f32) -> Self {
+		let bound = |px: Option<f32>, pct: Option<f32>, of: f32| match px {
+			Some(some) => Some(some),
+			None => pct.map(|pct| (pct / 100.0) * of)
+		};
+		
+		SizeBounds {
+			min_w: bound(style.min_width, style.min_width_pct, par_w),
+			max_w: bound(style.max_width, style.max_width_pct, par_w),
+			min_h: bound(style.min_height, style.min_height_pct, par_h),
+			max_h: bound(style.max_height, style.max_height_pct, par_h),
+		}
+	}
+	
+	fn width(&self, width: f32) -> f32 {
+		clamp_size(width, self.min_w, self.max_w)
+	}
+	
+	fn height(&self, height: f32) -> f32 {
+		clamp_size(height, self.min_h, self.max_h)
+	}
+}
+
+// The minimum is applied last so it wins when it is larger than the maximum.
+fn clamp_size(mut size: f32, min: Option<f32>, max: Option<f32>) -> f32 {
+	if let Some(max) = max {
+		if size > max {
+			size = max;
+		}
+	}
+	
+	if let Some(min) = min {
+		if size < min {
+			size = min;
+		}
+	}
+	
+	size
+}
+
+fn aspect_ratio(style: &BinStyle) -> Option<f32> {
+	style.aspect_ratio.filter(|ratio| *ratio > 0.0)
+}
+
+/// Apply the min/max sizes and aspect ratio of ``style``, percentages are of ``par_w`` and
+/// ``par_h``. Like css the precedence is:
+///
+/// 1. With an aspect ratio a missing width or height is derived from the other, or when both
+///    are present the larger one is reduced to match the ratio.
+/// 2. Each size is then limited by its maximum followed by its minimum, so the minimum wins
+///    when it is larger than the maximum and the bounds win over the aspect ratio.
+fn constrain_size(
+	style: &BinStyle,
+	width: Option<f32>,
+	height: Option<f32>,
+	par_w: f32,
+	par_h: f32
+) -> (Option<f32>, Option<f32>) {
+	let (width, height) = match aspect_ratio(style) {
+		Some(ratio) => (width.or(height.map(|height| height * ratio)), height.or(width.map(|width| width / ratio))),
+		None => (width, height)
+	};
+	
+	match (width, height) {
+		(Some(width), Some(height)) => {
+			let (width, height) = constrain_known_size(style, width, height, par_w, par_h);
+			(Some(width), Some(height))
+		},
+		(width, height) => {
+			let bounds = SizeBounds::new(style, par_w, par_h);
+			(width.map(|width| bounds.width(width)), height.map(|height| bounds.height(height)))
+		}
+	}
+}
+
+/// ``constrain_size`` for when both the width and height are known.
+fn constrain_known_size(style: &BinStyle, width: f32, height: f32, par_w: f32, par_h: f32) -> (f32, f32) {
+	let bounds = SizeBounds::new(style, par_w, par_h);
+	
+	let (width, height) = match aspect_ratio(style) {
+		Some(ratio) => if width > height * ratio {
+			(height * ratio, height)
+		} else {
+			(width, width / ratio)
+		},
+		None => (width, height)
+	};
+	
+	(bounds.width(width), bounds.height(height))
+}
+
+fn is_flow(style: &BinStyle) -> bool {
+	match style.position_t {
+		Some(PositionTy::Flow) => true,
+		_ => false
+	}
+}
+
+/// A flow child measured along (main) and across (cross) the flow direction.
+#[derive(Clone,Debug)]
+struct FlowItem {
+	main: f32,
+	cross: Option<f32>,
+	margin_main: (f32, f32),
+	margin_cross: (f32, f32),
+}
+
+impl FlowItem {
+	fn outer_main(&self) -> f32 {
+		self.main + self.margin_main.0 + self.margin_main.1
+	}
+	
+	fn outer_cross(&self) -> f32 {
+		self.cross.unwrap_or(0.0) + self.margin_cross.0 + self.margin_cross.1
+	}
+}
+
+/// Lays out a run of flow items. Returns the position along and across the flow direction
+/// followed by the main and cross size of each item, in the order of ``items``.
+fn flow_run(
+	items: &[FlowItem],
+	main_avail: f32,
+	cross_avail: f32,
+	wrap: bool,
+	gap: f32,
+	justify: &FlowJustify,
+	align: &FlowAlign
+) -> Vec<(f32, f32, f32, f32)> {
+	let mut lines: Vec<Vec<usize>> = vec![Vec::new()];
+	let mut line_main = 0.0;
+	
+	for (i, item) in items.iter().enumerate() {
+		if wrap && !lines.last().unwrap().is_empty() && line_main + gap + item.outer_main() > main_avail {
+			lines.push(Vec::new());
+			line_main = 0.0;
+		}
+		
+		if !lines.last().unwrap().is_empty() {
+			line_main += gap;
+		}
+		
+		line_main += item.outer_main();
+		lines.last_mut().unwrap().push(i);
+	}
+	
+	let single_line = lines.len() == 1;
+	let mut cross_at = 0.0;
+	let mut out = vec![(0.0, 0.0, 0.0, 0.0); items.len()];
+	
+	for line in lines {
+		let count = line.len() as f32;
+		let used = line.iter().map(|i| items[*i].outer_main()).sum::<f32>() + (gap * (count - 1.0).max(0.0));
+		let free = (main_avail - used).max(0.0);
+		
+		// A single line takes up the whole parent like absolutely positioned children would.
+		let line_cross = if single_line {
+			cross_avail
+		} else {
+			line.iter().map(|i| items[*i].outer_cross()).fold(0.0, f32::max)
+		};
+		
+		let (mut main_at, spacing) = match *justify {
+			FlowJustify::Start => (0.0, 0.0),
+			FlowJustify::End => (free, 0.0),
+			FlowJustify::Center => (free / 2.0, 0.0),
+			FlowJustify::SpaceBetween => if line.len() > 1 {
+				(0.0, free / (count - 1.0))
+			} else {
+				(0.0, 0.0)
+			},
+			FlowJustify::SpaceAround => (free / count / 2.0, free / count),
+		};
+		
+		for i in line {
+			let item = &items[i];
+			
+			let cross = match item.cross {
+				Some(some) => some,
+				None => match *align {
+					FlowAlign::Stretch => line_cross - item.margin_cross.0 - item.margin_cross.1,
+					_ => 0.0
+				}
+			};
+			
+			let cross_free = line_cross - cross - item.margin_cross.0 - item.margin_cross.1;
+			
+			let cross_pos = cross_at + item.margin_cross.0 + match *align {
+				FlowAlign::Start | FlowAlign::Stretch => 0.0,
+				FlowAlign::End => cross_free,
+				FlowAlign::Center => cross_free / 2.0,
+			};
+			
+			out[i] = (main_at + item.margin_main.0, cross_pos, item.main, cross);
+			main_at += item.outer_main() + gap + spacing;
+		}
+		
+		cross_at += line_cross + gap;
+	}
+	
+	out
+}
+
+/// Start and size of each track. Without any tracks there is a single track filling ``size``.
+fn grid_tracks(tracks: Option<&Vec<GridTrack>>, size: f32, gap: f32) -> Vec<(f32, f32)> {
+	let tracks = match tracks {
+		Some(some) if !some.is_empty() => some.clone(),
+		_ => vec![GridTrack::Fraction(1.0)]
+	};
+	
+	let mut used = gap * (tracks.len() - 1) as f32;
+	let mut fractions: f32 = 0.0;
+	
+	for track in &tracks {
+		match *track {
+			GridTrack::Fixed(amt) => used += amt,
+			GridTrack::Percent(pct) => used += (pct / 100.0) * size,
+			GridTrack::Fraction(fr) => fractions += fr,
+		}
+	}
+	
+	let per_fraction = if fractions > 0.0 {
+		(size - used).max(0.0) / fractions
+	} else {
+		0.0
+	};
+	
+	let mut out = Vec::with_capacity(tracks.len());
+	let mut at = 0.0;
+	
+	for track in &tracks {
+		let track_size = match *track {
+			GridTrack::Fixed(amt) => amt,
+			GridTrack::Percent(pct) => (pct / 100.0) * size,
+			GridTrack::Fraction(fr) => fr * per_fraction,
+		};
+		
+		out.push((at, track_size));
+		at += track_size + gap;
+	}
+	
+	out
+}
+
+#[derive(Clone,Debug,PartialEq)]
+pub enum FlowDir {
+	Row,
+	Column,
+}
+
+/// Placement of the children along the flow direction when there is space left.
+#[derive(Clone,Debug,PartialEq)]
+pub enum FlowJustify {
+	Start,
+	End,
+	Center,
+	SpaceBetween,
+	SpaceAround,
+}
+
+/// Placement of the children across the flow direction within their line.
+#[derive(Clone,Debug,PartialEq)]
+pub enum FlowAlign {
+	Start,
+	End,
+	Center,
+	/// Children without a size across the flow direction fill the line.
+	Stretch,
 }
 
 #[derive(Clone,Debug,PartialEq,Default)]
@@ -1612,3 +2535,177 @@ impl Color {
 	}	
 }
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn item(main: f32, cross: Option<f32>) -> FlowItem {
+		FlowItem {
+			main,
+			cross,
+			margin_main: (0.0, 0.0),
+			margin_cross: (0.0, 0.0),
+		}
+	}
+	
+	#[test]
+	fn flow_run_start() {
+		let items = vec![item(10.0, Some(5.0)), item(20.0, Some(5.0))];
+		let out = flow_run(&items, 100.0, 50.0, false, 2.0, &FlowJustify::Start, &FlowAlign::Start);
+		assert_eq!(out, vec![(0.0, 0.0, 10.0, 5.0), (12.0, 0.0, 20.0, 5.0)]);
+	}
+	
+	#[test]
+	fn flow_run_margins() {
+		let mut first = item(10.0, Some(5.0));
+		first.margin_main = (3.0, 4.0);
+		first.margin_cross = (1.0, 0.0);
+		let items = vec![first, item(10.0, Some(5.0))];
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::Start, &FlowAlign::Start);
+		assert_eq!(out, vec![(3.0, 1.0, 10.0, 5.0), (17.0, 0.0, 10.0, 5.0)]);
+	}
+	
+	#[test]
+	fn flow_run_justify() {
+		let items = vec![item(10.0, Some(5.0)), item(10.0, Some(5.0))];
+		
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::End, &FlowAlign::Start);
+		assert_eq!((out[0].0, out[1].0), (80.0, 90.0));
+		
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::Center, &FlowAlign::Start);
+		assert_eq!((out[0].0, out[1].0), (40.0, 50.0));
+		
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::SpaceBetween, &FlowAlign::Start);
+		assert_eq!((out[0].0, out[1].0), (0.0, 90.0));
+		
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::SpaceAround, &FlowAlign::Start);
+		assert_eq!((out[0].0, out[1].0), (20.0, 70.0));
+	}
+	
+	#[test]
+	fn flow_run_align() {
+		let items = vec![item(10.0, Some(10.0)), item(10.0, None)];
+		
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::Start, &FlowAlign::End);
+		assert_eq!((out[0].1, out[0].3), (40.0, 10.0));
+		
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::Start, &FlowAlign::Center);
+		assert_eq!((out[0].1, out[0].3), (20.0, 10.0));
+		
+		let out = flow_run(&items, 100.0, 50.0, false, 0.0, &FlowJustify::Start, &FlowAlign::Stretch);
+		assert_eq!((out[0].1, out[0].3), (0.0, 10.0));
+		assert_eq!((out[1].1, out[1].3), (0.0, 50.0));
+	}
+	
+	#[test]
+	fn flow_run_wrap() {
+		let items = vec![item(40.0, Some(5.0)), item(40.0, Some(8.0)), item(40.0, Some(5.0))];
+		
+		let out = flow_run(&items, 100.0, 50.0, true, 2.0, &FlowJustify::Start, &FlowAlign::Start);
+		assert_eq!(out, vec![(0.0, 0.0, 40.0, 5.0), (42.0, 0.0, 40.0, 8.0), (0.0, 10.0, 40.0, 5.0)]);
+		
+		// Without wrapping the items overflow the line.
+		let out = flow_run(&items, 100.0, 50.0, false, 2.0, &FlowJustify::Start, &FlowAlign::Start);
+		assert_eq!(out[2], (84.0, 0.0, 40.0, 5.0));
+	}
+	
+	#[test]
+	fn constrain_size_unconstrained() {
+		let style = BinStyle::default();
+		assert_eq!(constrain_size(&style, Some(10.0), None, 100.0, 100.0), (Some(10.0), None));
+		assert_eq!(constrain_size(&style, None, None, 100.0, 100.0), (None, None));
+	}
+	
+	#[test]
+	fn constrain_size_bounds() {
+		let style = BinStyle {
+			min_width: Some(20.0),
+			max_width: Some(40.0),
+			max_height_pct: Some(50.0),
+			.. BinStyle::default()
+		};
+		
+		assert_eq!(constrain_size(&style, Some(50.0), Some(150.0), 100.0, 200.0), (Some(40.0), Some(100.0)));
+		assert_eq!(constrain_size(&style, Some(10.0), None, 100.0, 200.0), (Some(20.0), None));
+		assert_eq!(constrain_size(&style, Some(30.0), None, 100.0, 200.0), (Some(30.0), None));
+	}
+	
+	#[test]
+	fn constrain_size_min_over_max() {
+		let style = BinStyle {
+			min_width: Some(60.0),
+			max_width: Some(40.0),
+			.. BinStyle::default()
+		};
+		
+		assert_eq!(constrain_size(&style, Some(50.0), None, 100.0, 100.0), (Some(60.0), None));
+		assert_eq!(constrain_size(&style, Some(30.0), None, 100.0, 100.0), (Some(60.0), None));
+	}
+	
+	#[test]
+	fn constrain_size_aspect_ratio() {
+		let style = BinStyle {
+			aspect_ratio: Some(2.0),
+			.. BinStyle::default()
+		};
+		
+		assert_eq!(constrain_size(&style, Some(100.0), None, 0.0, 0.0), (Some(100.0), Some(50.0)));
+		assert_eq!(constrain_size(&style, None, Some(30.0), 0.0, 0.0), (Some(60.0), Some(30.0)));
+		// With both sizes the larger one is reduced.
+		assert_eq!(constrain_size(&style, Some(100.0), Some(100.0), 0.0, 0.0), (Some(100.0), Some(50.0)));
+		assert_eq!(constrain_size(&style, Some(40.0), Some(100.0), 0.0, 0.0), (Some(40.0), Some(20.0)));
+		assert_eq!(constrain_known_size(&style, 100.0, 100.0, 0.0, 0.0), (100.0, 50.0));
+		
+		let ignored = BinStyle {
+			aspect_ratio: Some(0.0),
+			.. BinStyle::default()
+		};
+		
+		assert_eq!(constrain_size(&ignored, Some(100.0), None, 0.0, 0.0), (Some(100.0), None));
+	}
+	
+	#[test]
+	fn constrain_size_bounds_over_aspect_ratio() {
+		let style = BinStyle {
+			aspect_ratio: Some(2.0),
+			max_height: Some(40.0),
+			.. BinStyle::default()
+		};
+		
+		assert_eq!(constrain_size(&style, Some(200.0), None, 0.0, 0.0), (Some(200.0), Some(40.0)));
+		assert_eq!(constrain_size(&style, Some(200.0), Some(200.0), 0.0, 0.0), (Some(200.0), Some(40.0)));
+		
+		let style = BinStyle {
+			aspect_ratio: Some(2.0),
+			min_height: Some(80.0),
+			.. BinStyle::default()
+		};
+		
+		assert_eq!(constrain_size(&style, Some(100.0), None, 0.0, 0.0), (Some(100.0), Some(80.0)));
+	}
+	
+	#[test]
+	fn grid_tracks_mixed() {
+		let tracks = vec![GridTrack::Fixed(20.0), GridTrack::Percent(25.0), GridTrack::Fraction(1.0), GridTrack::Fraction(3.0)];
+		assert_eq!(grid_tracks(Some(&tracks), 200.0, 10.0), vec![(0.0, 20.0), (30.0, 50.0), (90.0, 25.0), (125.0, 75.0)]);
+	}
+	
+	#[test]
+	fn grid_tracks_default() {
+		assert_eq!(grid_tracks(None, 80.0, 10.0), vec![(0.0, 80.0)]);
+		assert_eq!(grid_tracks(Some(&Vec::new()), 80.0, 10.0), vec![(0.0, 80.0)]);
+	}
+	
+	#[test]
+	fn grid_tracks_overflow() {
+		// Fractions get nothing once the fixed tracks use up the space.
+		let tracks = vec![GridTrack::Fixed(60.0), GridTrack::Fraction(1.0), GridTrack::Fixed(60.0)];
+		assert_eq!(grid_tracks(Some(&tracks), 100.0, 0.0), vec![(0.0, 60.0), (60.0, 0.0), (60.0, 60.0)]);
+	}
+	
+	#[test]
+	fn flow_run_empty() {
+		assert!(flow_run(&[], 100.0, 50.0, true, 2.0, &FlowJustify::SpaceAround, &FlowAlign::Start).is_empty());
+	}
+}
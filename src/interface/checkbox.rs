@@ -2,7 +2,7 @@ use Basalt;
 use std::sync::Arc;
 use super::bin::{KeepAlive,Bin,BinStyle};
 use parking_lot::Mutex;
-use std::thread;
+use std::time::Duration;
 use input::*;
 
 /// Simple checkbox. Provides a change hook and the ability to get the state.
@@ -53,14 +53,14 @@ impl CheckBox {
 			Some(some) => some,
 			None => self.is_checked()
 		};
-	
-		let on_change = self.on_change.lock().clone().into_iter();
 		
-		thread::spawn(move || {
-			for func in on_change {
+		let on_change = self.on_change.lock().clone();
+		
+		self.basalt.scheduler_ref().set_timeout(Duration::from_millis(0), Arc::new(move || {
+			for func in &on_change {
 				func(checked);
 			}
-		});
+		}));
 	}
 	
 	fn update(&self, checked_op: Option<bool>) {
@@ -4,7 +4,8 @@ use std::sync::Arc;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use parking_lot::Mutex;
-use interface::bin::Bin;
+use interface::bin::{Bin,BinStyle,BinCursor,PositionTy};
+use interface::interface::Interface;
 use Basalt;
 use std::sync::Weak;
 use crossbeam::channel::{self,Sender};
@@ -36,6 +37,7 @@ pub enum BinHookTy {
 	MouseLeave,
 	MouseMove,
 	MouseScroll,
+	MouseScrollX,
 	Focused,
 	LostFocus,
 }
@@ -64,6 +66,7 @@ pub enum BinHook {
 	MouseLeave,
 	MouseMove,
 	MouseScroll,
+	MouseScrollX,
 	Focused,
 	LostFocus,
 }
@@ -175,6 +178,10 @@ impl BinHook {
 				scroll_amt: 0.0,
 			},
 			
+			BinHook::MouseScrollX => BinHookData::MouseScrollX {
+				scroll_amt: 0.0,
+			},
+			
 			BinHook::Focused => BinHookData::Focused,
 			BinHook::LostFocus => BinHookData::LostFocus,
 		}
@@ -236,6 +243,10 @@ pub enum BinHookData {
 		scroll_amt: f32,
 	},
 	
+	MouseScrollX {
+		scroll_amt: f32,
+	},
+	
 	Focused,
 	LostFocus,
 }
@@ -248,6 +259,7 @@ pub(crate) enum InputEvent {
 	MousePosition(f32, f32),
 	MouseDelta(f32, f32),
 	Scroll(f32),
+	ScrollX(f32),
 }
 
 impl BinHookData {
@@ -261,6 +273,7 @@ impl BinHookData {
 			BinHookData::MouseLeave { .. } => BinHookTy::MouseLeave,
 			BinHookData::MouseMove { .. } => BinHookTy::MouseMove,
 			BinHookData::MouseScroll { .. } => BinHookTy::MouseScroll,
+			BinHookData::MouseScrollX { .. } => BinHookTy::MouseScrollX,
 			BinHookData::Focused => BinHookTy::Focused,
 			BinHookData::LostFocus => BinHookTy::LostFocus,
 		}
@@ -298,28 +311,154 @@ struct SmoothScroll {
 	at: f32,
 }
 
+impl SmoothScroll {
+	/// Add the scroll received this tick and return the amount to scroll by.
+	fn step(&mut self, scroll_amt: f32) -> f32 {
+		if scroll_amt != 0.0 {
+			if SMOOTH_SCROLL_ACCEL {
+				self.to += scroll_amt * SMOOTH_SROLLL_STEP_MULT
+					* ((self.to).abs() + SMOOTH_SCROLL_ACCEL_FACTOR).log(SMOOTH_SCROLL_ACCEL_FACTOR);
+			} else {
+				self.to += scroll_amt * SMOOTH_SROLLL_STEP_MULT;
+			}
+		}
+		
+		if self.at != 0.0 || self.to != 0.0 {
+			if self.at == self.to {
+				self.at = 0.0;
+				self.to = 0.0;
+			} else {
+				let diff = self.to - self.at;
+				let step = diff * 0.175;
+				
+				let amt = if f32::abs(step) < 0.005 {
+					diff
+				} else {
+					step
+				};
+				
+				self.at += amt;
+				return amt;
+			}
+		}
+		
+		0.0
+	}
+}
+
+#[derive(Default)]
+struct CursorState {
+	current: BinCursor,
+	/// Bin used to draw image cursors, follows the mouse.
+	bin: Option<Arc<Bin>>,
+	hotspot: [f32; 2],
+}
+
 pub(crate) struct HookManager {
 	focused: Mutex<Option<u64>>,
 	hooks: Mutex<BTreeMap<BinHookID, (Weak<Bin>, BinHookData, BinHookFn)>>,
 	current_id: Mutex<u64>,
-	basalt: Arc<Basalt>,
+	basalt: Weak<Basalt>,
+	interface: Mutex<Weak<Interface>>,
 	events: Sender<InputEvent>,
 	remove: Sender<BinHookID>,
 	add: Sender<(BinHookID, (Weak<Bin>, BinHookData, BinHookFn))>,
 }
 
 impl HookManager {
+	pub(crate) fn set_interface(&self, interface: &Arc<Interface>) {
+		*self.interface.lock() = Arc::downgrade(interface);
+	}
+	
+	fn get_bin_atop(&self, x: f32, y: f32) -> Option<Arc<Bin>> {
+		self.interface.lock().upgrade().and_then(|itf| itf.get_bin_atop(x, y))
+	}
+	
+	fn update_cursor(&self, state: &mut CursorState, cursor: BinCursor, x: f32, y: f32) {
+		let interface = match self.interface.lock().upgrade() {
+			Some(some) => some,
+			None => return
+		};
+		
+		let basalt = match self.basalt.upgrade() {
+			Some(some) => some,
+			None => return
+		};
+		
+		// The cursor is hidden while captured, leave it alone.
+		if basalt.mouse_captured() && Arc::ptr_eq(&interface, basalt.interface_ref()) {
+			return;
+		}
+		
+		if cursor != state.current {
+			if let Some(surface) = interface.surface() {
+				match cursor.winit_cursor() {
+					Some(winit_cursor) => {
+						surface.window().set_cursor(winit_cursor);
+						surface.window().hide_cursor(false);
+					}, None => surface.window().hide_cursor(true)
+				}
+			}
+			
+			let image = match &cursor {
+				BinCursor::Image { path, size, hotspot } => Some((Some(path.clone()), None, *size, *hotspot)),
+				BinCursor::ImageUrl { url, size, hotspot } => Some((None, Some(url.clone()), *size, *hotspot)),
+				_ => None
+			};
+			
+			match image {
+				Some((back_image, back_image_url, size, hotspot)) => {
+					if state.bin.is_none() {
+						let bin = match interface.new_bin() {
+							Some(some) => some,
+							None => return
+						};
+						
+						bin.basalt_use();
+						state.bin = Some(bin);
+					}
+					
+					state.hotspot = hotspot;
+					
+					state.bin.as_ref().unwrap().style_update(BinStyle {
+						position_t: Some(PositionTy::FromWindow),
+						pass_events: Some(true),
+						pos_from_t: Some(0.0),
+						pos_from_l: Some(0.0),
+						width: Some(size[0]),
+						height: Some(size[1]),
+						back_image,
+						back_image_url,
+						.. BinStyle::default()
+					});
+				}, None => {
+					state.bin = None;
+				}
+			}
+			
+			state.current = cursor;
+		}
+		
+		if let Some(bin) = state.bin.as_ref() {
+			let scale = interface.scale();
+			let mut style = bin.style_copy();
+			style.pos_from_t = Some((y / scale) - state.hotspot[1]);
+			style.pos_from_l = Some((x / scale) - state.hotspot[0]);
+			bin.style_update(style);
+		}
+	}
+	
 	pub fn send_event(&self, event: InputEvent) {
-		self.events.send(event).unwrap();
+		let _ = self.events.send(event);
 	}
 	
 	pub fn remove_hook(&self, hook_id: BinHookID) {
-		self.remove.send(hook_id).unwrap();
+		let _ = self.remove.send(hook_id);
 	}
 	
 	pub fn remove_hooks(&self, hook_ids: Vec<BinHookID>) {
 		for hook_id in hook_ids {
-			self.remove.send(hook_id).unwrap();
+			let _ = self.remove.send(hook_id);
 		}
 	}
 	
@@ -328,7 +467,7 @@ impl HookManager {
 		let id = BinHookID(*current_id);
 		*current_id += 1;
 		drop(current_id);
-		self.add.send((id, (Arc::downgrade(&bin), hook.into_data(), func))).unwrap();
+		let _ = self.add.send((id, (Arc::downgrade(&bin), hook.into_data(), func)));
 		id
 	}
 
@@ -341,7 +480,8 @@ impl HookManager {
 			focused: Mutex::new(None),
 			hooks: Mutex::new(BTreeMap::new()),
 			current_id: Mutex::new(0),
-			basalt,
+			basalt: Arc::downgrade(&basalt),
+			interface: Mutex::new(Weak::new()),
 			events: events_s,
 			remove: remove_s,
 			add: add_s,
@@ -356,13 +496,14 @@ impl HookManager {
 			MouseLeave(X)
 			MouseMove(X) Delta should be zero on first call?
 			MouseScroll(X) Smooth scroll isn't work for some reason
+			MouseScrollX(X) Shift + wheel is also horizontal
 			Focused(X)
 			LostFocus(X)
 		*/
 		
 		let hman = hman_ret.clone();
 		
-		::std::thread::spawn(move || {
+		basalt.track_thread(::std::thread::spawn(move || {
 			let mut last_tick = Instant::now();
 			let tick_interval = Duration::from_millis(5);
 			let char_initial_hold_delay = 200; // Time in ticks
@@ -375,12 +516,21 @@ impl HookManager {
 			let mut key_state = HashMap::new();
 			let mut mouse_state = HashMap::new();
 			let mut smooth_scroll = SmoothScroll::default();
+			let mut smooth_scroll_x = SmoothScroll::default();
 			let mut mouse_in: HashMap<u64, Weak<Bin>> = HashMap::new();
+			let mut cursor_state = CursorState::default();
 			
 			loop {
+				match hman.basalt.upgrade() {
+					Some(basalt) => if basalt.wants_exit() {
+						break;
+					}, None => break
+				}
+				
 				let mut focused = hman.focused.lock();
 				let mut hooks = hman.hooks.lock();
 				let mut m_scroll_amt = 0.0;
+				let mut m_scroll_x_amt = 0.0;
 				let mut events = Vec::new();
 				let mut bad_hooks = Vec::new();
 				
@@ -403,6 +553,8 @@ impl HookManager {
 							m_moved = true;
 						}, InputEvent::Scroll(y) => {
 							m_scroll_amt += y;
+						}, InputEvent::ScrollX(x) => {
+							m_scroll_x_amt += x;
 						}, InputEvent::MousePress(button) => {
 							let mut modified = false;
 						
@@ -465,8 +617,12 @@ impl HookManager {
 				
 				if m_moved {
 					let mut in_bins = Vec::new();
-				
-					if let Some(top_bin) = hman.basalt.interface_ref().get_bin_atop(m_window_x, m_window_y) {
+					
+					let top_bin_op = hman.get_bin_atop(m_window_x, m_window_y);
+					let cursor = top_bin_op.as_ref().map(|b| b.cursor()).unwrap_or_default();
+					hman.update_cursor(&mut cursor_state, cursor, m_window_x, m_window_y);
+					
+					if let Some(top_bin) = top_bin_op {
 						in_bins.push(top_bin.clone());				
 						in_bins.append(&mut top_bin.ancestors());
 						
@@ -562,39 +718,12 @@ impl HookManager {
 				}
 				
 				if SMOOTH_SCROLL {
-					if m_scroll_amt != 0.0 {
-						if SMOOTH_SCROLL_ACCEL {
-							smooth_scroll.to += m_scroll_amt * SMOOTH_SROLLL_STEP_MULT
-								* ((smooth_scroll.to).abs() + SMOOTH_SCROLL_ACCEL_FACTOR).log(SMOOTH_SCROLL_ACCEL_FACTOR);
-						} else {
-							smooth_scroll.to += m_scroll_amt * SMOOTH_SROLLL_STEP_MULT;
-						}
-					}
-					
-					m_scroll_amt = 0.0;
-					
-					if smooth_scroll.at != 0.0 || smooth_scroll.to != 0.0 {
-						if smooth_scroll.at == smooth_scroll.to {
-							smooth_scroll.at = 0.0;
-							smooth_scroll.to = 0.0;
-						} else {
-							let diff = smooth_scroll.to - smooth_scroll.at;
-							let step = diff * 0.175;
-							
-							let amt = if f32::abs(step) < 0.005 {
-								diff
-							} else {
-								step
-							};
-							
-							smooth_scroll.at += amt;
-							m_scroll_amt = amt;
-						}
-					}
+					m_scroll_amt = smooth_scroll.step(m_scroll_amt);
+					m_scroll_x_amt = smooth_scroll_x.step(m_scroll_x_amt);
 				}
 				
 				if m_scroll_amt != 0.0 {
-					if let Some(top_bin) = hman.basalt.interface_ref().get_bin_atop(m_window_x, m_window_y) {
+					if let Some(top_bin) = hman.get_bin_atop(m_window_x, m_window_y) {
 						let mut in_bins = vec![top_bin.clone()];
 						in_bins.append(&mut top_bin.ancestors());
 						
@@ -623,10 +752,40 @@ impl HookManager {
 					}
 				}
 				
+				if m_scroll_x_amt != 0.0 {
+					if let Some(top_bin) = hman.get_bin_atop(m_window_x, m_window_y) {
+						let mut in_bins = vec![top_bin.clone()];
+						in_bins.append(&mut top_bin.ancestors());
+						
+						'bin_x_loop: for bin in in_bins {
+							for (hook_id, (hb_wk, hook, func)) in &mut *hooks {
+								let hb = match hb_wk.upgrade() {
+									Some(some) => some,
+									None => {
+										bad_hooks.push(hook_id.clone());
+										continue;
+									}
+								};
+								
+								if hb.id() == bin.id() {
+									if hook.ty() == BinHookTy::MouseScrollX {
+										if let BinHookData::MouseScrollX { scroll_amt, .. } = hook {
+											*scroll_amt = m_scroll_x_amt;
+										}
+										
+										func(hb.clone(), hook); // Call MouseScrollX
+										break 'bin_x_loop;
+									}
+								}
+							}
+						}
+					}
+				}
+				
 				for event in events {
 					match event {
 						InputEvent::MousePress(button) => {
-							let mut top_bin_op = hman.basalt.interface_ref().get_bin_atop(m_window_x, m_window_y);
+							let mut top_bin_op = hman.get_bin_atop(m_window_x, m_window_y);
 							
 							if top_bin_op.as_ref().map(|v| v.id()) != *focused {
 								if let Some(bin_id) = &*focused {
@@ -974,8 +1133,15 @@ impl HookManager {
 													let r = key_state.get(&Qwery::RShift).cloned().unwrap_or(0);
 													l > 0 || r > 0
 												};
-											
-												if let Some(c) = key.into_char(shift) {
+												
+												// Ctrl combinations are shortcuts, not text.
+												let ctrl = {
+													let l = key_state.get(&Qwery::LCtrl).cloned().unwrap_or(0);
+													let r = key_state.get(&Qwery::RCtrl).cloned().unwrap_or(0);
+													l > 0 || r > 0
+												};
+												
+												if let Some(c) = key.into_char(shift).filter(|_| !ctrl) {
 													if let BinHookData::Character { char_ty, .. } = hook {
 														*char_ty = c;
 													}
@@ -1076,6 +1242,12 @@ impl HookManager {
 					l > 0 || r > 0
 				};
 				
+				let ctrl = {
+					let l = key_state.get(&Qwery::LCtrl).cloned().unwrap_or(0);
+					let r = key_state.get(&Qwery::RCtrl).cloned().unwrap_or(0);
+					l > 0 || r > 0
+				};
+				
 				for (key, state) in &mut key_state {
 					if *state > 0 {
 						if *state < char_initial_hold_delay + char_repeat_delay {
@@ -1086,7 +1258,7 @@ impl HookManager {
 							*state = char_initial_hold_delay;
 						}
 						
-						if *state == char_initial_hold_delay {
+						if *state == char_initial_hold_delay && !ctrl {
 							for (hook_id, (hb_wk, hook, func)) in &mut *hooks {
 								let hb = match hb_wk.upgrade() {
 									Some(some) => some,
@@ -1186,8 +1358,8 @@ impl HookManager {
 				
 				last_tick = Instant::now();
 			}
-		});	
-			
+		}));
+		
 		hman_ret
 	}
 }
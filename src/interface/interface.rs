@@ -1,11 +1,16 @@
 use std::sync::{Arc,Weak};
 use std::collections::BTreeMap;
 use Basalt;
+use BasaltEvent;
 use super::bin::Bin;
 use parking_lot::{Mutex,RwLock};
 use interface::text::Text;
 use interface::odb::OrderedDualBuffer;
 use interface::hook::HookManager;
+use interface::render::ItfRenderer;
+use error::{BasaltError,RenderError};
+use vulkano::swapchain::Surface;
+use winit::Window;
 
 impl_vertex!(ItfVertInfo, position, coords, color, ty);
 #[derive(Clone)]
@@ -52,7 +57,7 @@ pub(crate) enum ItfEvent {
 }
 
 pub struct Interface {
-	basalt: Arc<Basalt>,
+	basalt: Weak<Basalt>,
 	text: Arc<Text>,
 	bin_i: Mutex<u64>,
 	bin_map: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>>,
@@ -61,9 +66,17 @@ pub struct Interface {
 	pub(crate) odb: Arc<OrderedDualBuffer>,
 	pub(crate) itf_events: Mutex<Vec<ItfEvent>>,
 	pub(crate) hook_manager: Arc<HookManager>,
+	/// Renderer used by ``Bin::capture()``, created on first use. Cleared when the interface's
+	/// window or basalt shuts down since it holds onto both.
+	pub(crate) capture_renderer: Mutex<Option<ItfRenderer>>,
 }
 
 impl Interface {
+	/// ``None`` once Basalt has been dropped, in which case there is nothing left to do.
+	fn basalt(&self) -> Option<Arc<Basalt>> {
+		self.basalt.upgrade()
+	}
+	
 	pub(crate) fn scale(&self) -> f32 {
 		*self.scale.lock()
 	}
@@ -71,23 +84,46 @@ impl Interface {
 	pub(crate) fn set_scale(&self, to: f32) {
 		*self.scale.lock() = to;
 		self.itf_events.lock().push(ItfEvent::ScaleChanged);
+		
+		if let Some(basalt) = self.basalt() {
+			basalt.request_redraw();
+			basalt.send_event(BasaltEvent::ScaleChanged(to));
+		}
+	}
+	
+	/// Surface of the window this interface is displayed in, ``None`` when headless.
+	pub(crate) fn surface(self: &Arc<Self>) -> Option<Arc<Surface<Window>>> {
+		let basalt = self.basalt()?;
+		
+		if Arc::ptr_eq(self, basalt.interface_ref()) {
+			basalt.surface()
+		} else {
+			basalt.windows().into_iter()
+				.find(|window| Arc::ptr_eq(window.interface_ref(), self))
+				.and_then(|window| window.surface())
+		}
 	}
 	
 	pub fn msaa(&self) -> u32 {
 		*self.msaa.lock()
 	}
 	
-	pub fn set_msaa(&self, amt: u32) -> Result<(), String> {
+	pub fn set_msaa(&self, amt: u32) -> Result<(), BasaltError> {
 		let amt = match amt {
 			1 => 1,
 			2 => 2,
 			4 => 4,
 			8 => 8,
-			a => return Err(format!("Invalid MSAA amount {}X", a))
+			a => return Err(RenderError::InvalidMSAA(a).into())
 		};
 		
 		*self.msaa.lock() = amt;
 		self.itf_events.lock().push(ItfEvent::MSAAChanged);
+		
+		if let Some(basalt) = self.basalt() {
+			basalt.request_redraw();
+			basalt.send_event(BasaltEvent::MSAAChanged(amt));
+		}
 		Ok(())
 	}
 	
@@ -102,7 +138,14 @@ impl Interface {
 			_ => panic!("Invalid MSAA level set!")
 		};
 		
+		let amt = *msaa;
+		drop(msaa);
 		self.itf_events.lock().push(ItfEvent::MSAAChanged);
+		
+		if let Some(basalt) = self.basalt() {
+			basalt.request_redraw();
+			basalt.send_event(BasaltEvent::MSAAChanged(amt));
+		}
 	}
 	
 	pub fn decrease_msaa(&self) {
@@ -116,14 +159,26 @@ impl Interface {
 			_ => panic!("Invalid MSAA level set!")
 		};
 		
+		let amt = *msaa;
+		drop(msaa);
 		self.itf_events.lock().push(ItfEvent::MSAAChanged);
+		
+		if let Some(basalt) = self.basalt() {
+			basalt.request_redraw();
+			basalt.send_event(BasaltEvent::MSAAChanged(amt));
+		}
 	}
 	
 	pub(crate) fn new(basalt: Arc<Basalt>) -> Arc<Self> {
-		let bin_map: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>> = Arc::new(RwLock::new(BTreeMap::new()));
 		let text = Text::new(basalt.clone());
+		Interface::with_text(basalt, text)
+	}
+	
+	/// Used by additional windows so that the text cache is shared.
+	pub(crate) fn with_text(basalt: Arc<Basalt>, text: Arc<Text>) -> Arc<Self> {
+		let bin_map: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>> = Arc::new(RwLock::new(BTreeMap::new()));
 		
-		Arc::new(Interface {
+		let interface = Arc::new(Interface {
 			odb: OrderedDualBuffer::new(basalt.clone(), bin_map.clone()),
 			bin_i: Mutex::new(0),
 			bin_map: bin_map,
@@ -131,8 +186,23 @@ impl Interface {
 			msaa: Mutex::new(4),
 			itf_events: Mutex::new(Vec::new()),
 			hook_manager: HookManager::new(basalt.clone()),
-			basalt, text,
-		})
+			capture_renderer: Mutex::new(None),
+			basalt: Arc::downgrade(&basalt),
+			text,
+		});
+		
+		interface.hook_manager.set_interface(&interface);
+		interface
+	}
+	
+	/// True when no bins are waiting to be updated and neither the buffer or atlas
+	/// have any work pending.
+	pub(crate) fn is_idle(&self) -> bool {
+		let bins: Vec<Arc<Bin>> = self.bin_map.read().iter().filter_map(|(_, b)| b.upgrade()).collect();
+		
+		!bins.iter().any(|b| b.wants_update())
+			&& self.odb.is_idle()
+			&& self.basalt().map(|basalt| basalt.atlas_ref().is_idle()).unwrap_or(true)
 	}
 	
 	pub(crate) fn text_ref(&self) -> &Arc<Text> {
@@ -185,7 +255,13 @@ impl Interface {
 		self.bin_map.read().iter().filter_map(|(_, b)| b.upgrade()).collect()
 	}
 	
-	pub fn new_bins(&self, amt: usize) -> Vec<Arc<Bin>> {
+	/// Returns no bins once Basalt has been dropped.
+	pub fn new_bins(self: &Arc<Self>, amt: usize) -> Vec<Arc<Bin>> {
+		let basalt = match self.basalt() {
+			Some(some) => some,
+			None => return Vec::new()
+		};
+		
 		let mut out = Vec::with_capacity(amt);
 		let mut bin_i = self.bin_i.lock();
 		let mut bin_map = self.bin_map.write();
@@ -193,7 +269,7 @@ impl Interface {
 		for _ in 0..amt {
 			let id = *bin_i;
 			*bin_i += 1;
-			let bin = Bin::new(id.clone(), self.basalt.clone());
+			let bin = Bin::new(id.clone(), basalt.clone(), self.clone());
 			bin_map.insert(id, Arc::downgrade(&bin));
 			out.push(bin);
 		}
@@ -201,8 +277,9 @@ impl Interface {
 		out
 	}
 	
-	pub fn new_bin(&self) -> Arc<Bin> {
-		self.new_bins(1).pop().unwrap()
+	/// ``None`` once Basalt has been dropped, see ``new_bins``.
+	pub fn new_bin(self: &Arc<Self>) -> Option<Arc<Bin>> {
+		self.new_bins(1).pop()
 	}
 	
 	pub fn get_bin(&self, id: u64) -> Option<Arc<Bin>> {
@@ -11,6 +11,7 @@ use vulkano::buffer::BufferSlice;
 use vulkano::command_buffer::AutoCommandBufferBuilder;
 use Basalt;
 use vulkano::buffer::BufferUsage;
+use vulkano::device::{Device,Queue};
 use std::thread;
 use vulkano::buffer::cpu_access::CpuAccessibleBuffer;
 use vulkano::command_buffer::CommandBuffer;
@@ -20,11 +21,12 @@ use decorum::R32;
 use atlas;
 use std::collections::HashMap;
 use crossbeam::sync::{Parker,Unparker};
+use std::sync::atomic::{self,AtomicBool};
 
 const VERT_SIZE: usize = ::std::mem::size_of::<ItfVertInfo>();
 
 pub struct OrderedDualBuffer {
-	basalt: Arc<Basalt>,
+	basalt: Weak<Basalt>,
 	active: Mutex<Buffer>,
 	inactive: Mutex<Buffer>,
 	atlas_draw: Mutex<Option<HashMap<atlas::AtlasImageID, Arc<ImageViewAccess + Send + Sync>>>>,
@@ -34,10 +36,13 @@ pub struct OrderedDualBuffer {
 	)>>,
 	park: Mutex<Parker>,
 	unpark: Unparker,
+	idle: AtomicBool,
 }
 
 struct Buffer {
-	basalt: Arc<Basalt>,
+	device: Arc<Device>,
+	graphics_queue: Arc<Queue>,
+	transfer_queue: Arc<Queue>,
 	chunks: Vec<Chunk>,
 	bins: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>>,
 	buffer_op: Option<Arc<DeviceLocalBuffer<[ItfVertInfo]>>>,
@@ -65,13 +70,15 @@ struct Chunk {
 }
 
 impl Buffer {
-	fn new(basalt: Arc<Basalt>, bins: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>>) -> Self {
+	fn new(basalt: &Arc<Basalt>, bins: Arc<RwLock<BTreeMap<u64, Weak<Bin>>>>) -> Self {
 		Buffer {
 			bins,
 			chunks: Vec::new(),
 			buffer_op: None,
 			draw_sets: Vec::new(),
-			basalt,
+			device: basalt.device(),
+			graphics_queue: basalt.graphics_queue(),
+			transfer_queue: basalt.transfer_queue(),
 			resize: false,
 			win_size: [1920.0, 1080.0],
 			scale: 1.0,
@@ -228,10 +235,10 @@ impl Buffer {
 		
 		let dst_buf = unsafe {
 			DeviceLocalBuffer::raw(
-				self.basalt.device(),
+				self.device.clone(),
 				dst_len * VERT_SIZE,
 				BufferUsage::all(),
-				vec![self.basalt.graphics_queue().family()]
+				vec![self.graphics_queue.family()]
 			).unwrap()
 		};
 		
@@ -265,14 +272,14 @@ impl Buffer {
 			None
 		} else {
 			Some(CpuAccessibleBuffer::from_iter(
-				self.basalt.device(), BufferUsage::all(),
+				self.device.clone(), BufferUsage::all(),
 				src_data.into_iter()
 			).unwrap())	
 		};
 		
 		let mut cmd_builder = AutoCommandBufferBuilder::new(
-			self.basalt.device(),
-			self.basalt.transfer_queue_ref().family()
+			self.device.clone(),
+			self.transfer_queue.family()
 		).unwrap();
 		
 		for (src, dst, len) in copy_from_src {
@@ -294,7 +301,7 @@ impl Buffer {
 		}
 		
 		let cmd_buf = cmd_builder.build().unwrap();
-		let future = cmd_buf.execute(self.basalt.transfer_queue()).unwrap();
+		let future = cmd_buf.execute(self.transfer_queue.clone()).unwrap();
 		self.draw_sets = Vec::new();
 		
 		if !self.chunks.is_empty() {
@@ -346,21 +353,32 @@ impl OrderedDualBuffer {
 		let unpark = park.unparker().clone();
 		
 		let odb = Arc::new(OrderedDualBuffer {
-			basalt: basalt.clone(),
-			active: Mutex::new(Buffer::new(basalt.clone(), bins.clone())),
-			inactive: Mutex::new(Buffer::new(basalt.clone(), bins)),
+			basalt: Arc::downgrade(&basalt),
+			active: Mutex::new(Buffer::new(&basalt, bins.clone())),
+			inactive: Mutex::new(Buffer::new(&basalt, bins)),
 			atlas_draw: Mutex::new(None),
 			draw_sets: Mutex::new(Vec::new()),
 			park: Mutex::new(park),
 			unpark,
+			idle: AtomicBool::new(false),
 		});
 		let odb_ret = odb.clone();
 		
-		thread::spawn(move || {
+		basalt.track_thread(thread::spawn(move || {
 			let mut force_update = false;
 			let mut update_draw = false;
+			let mut atlas_version = 0;
 			
 			loop {
+				let basalt = match odb.basalt.upgrade() {
+					Some(some) => some,
+					None => break
+				};
+				
+				if basalt.wants_exit() {
+					break;
+				}
+				
 				if force_update {
 					let mut inactive = odb.inactive.lock();
 					inactive.resize = true;
@@ -391,7 +409,7 @@ impl OrderedDualBuffer {
 				drop(inactive);
 				let mut draw_op = odb.atlas_draw.lock();
 				
-				if let Some(draw_info) = odb.basalt.atlas_ref().draw_info() {
+				if let Some(draw_info) = basalt.atlas_ref().draw_info_since(&mut atlas_version) {
 					*draw_op = Some(draw_info);
 					force_update = true;
 					continue;
@@ -403,36 +421,57 @@ impl OrderedDualBuffer {
 					if let Some(draw) = draw_op.as_ref() {	
 						for (buf, atlas_img_id, image_op) in &odb.active.lock().draw_sets {
 							let img: Arc<ImageViewAccess + Send + Sync> = match atlas_img_id {
-								&0 => odb.basalt.atlas_ref().empty_image(),
+								&0 => basalt.atlas_ref().empty_image(),
 								&::std::u64::MAX => match image_op {
 									&Some(ref some) => some.clone(),
-									&None => odb.basalt.atlas_ref().empty_image()
+									&None => basalt.atlas_ref().empty_image()
 								}, img_id => match draw.get(img_id) {
 									Some(some) => some.clone(),
-									None => odb.basalt.atlas_ref().empty_image()
+									None => basalt.atlas_ref().empty_image()
 								}
 							};
 							
-							let sampler = odb.basalt.atlas_ref().default_sampler();
+							let sampler = basalt.atlas_ref().default_sampler();
 							draw_sets.push((buf.clone(), img, sampler));
 						}
 					}
 					
 					*odb.draw_sets.lock() = draw_sets;
 					update_draw = false;
+					basalt.request_redraw();
 				}
 				
+				drop(basalt);
+				odb.idle.store(true, atomic::Ordering::SeqCst);
 				odb.park.lock().park();
+				odb.idle.store(false, atomic::Ordering::SeqCst);
 			}
-		});
+		}));
 		
 		odb_ret
 	}
 	
 	pub(crate) fn unpark(&self) {
+		self.idle.store(false, atomic::Ordering::SeqCst);
 		self.unpark.unpark()
 	}
 	
+	/// True when the buffer thread has nothing left to do.
+	pub(crate) fn is_idle(&self) -> bool {
+		self.idle.load(atomic::Ordering::SeqCst)
+	}
+	
+	/// The window size the bins were last updated with.
+	pub(crate) fn win_size(&self) -> [u32; 2] {
+		let win_size = self.active.lock().win_size;
+		[win_size[0] as u32, win_size[1] as u32]
+	}
+	
+	/// The current image for an atlas image id, ``None`` if it hasn't been uploaded yet.
+	pub(crate) fn atlas_image(&self, atlas_img_id: atlas::AtlasImageID) -> Option<Arc<ImageViewAccess + Send + Sync>> {
+		self.atlas_draw.lock().as_ref().and_then(|draw| draw.get(&atlas_img_id)).cloned()
+	}
+	
 	pub(crate) fn draw_data(&self, win_size: [u32; 2], resize: bool, scale: f32) -> Vec<(
 		BufferSlice<[ItfVertInfo], Arc<DeviceLocalBuffer<[ItfVertInfo]>>>,
 		Arc<ImageViewAccess + Send + Sync>,
@@ -449,7 +488,7 @@ impl OrderedDualBuffer {
 				inactive.resize = resize;
 				inactive.win_size = win_size;
 				inactive.scale = scale;
-				self.unpark.unpark();
+				self.unpark();
 			}, false => ()
 		}
 		
@@ -22,7 +22,27 @@ use Basalt;
 use interface::interface::ItfVertInfo;
 use shaders;
 use parking_lot::Mutex;
-use interface::interface::ItfEvent;
+use interface::interface::{Interface,ItfEvent};
+use vulkano::buffer::BufferAccess;
+use vulkano::sampler::Sampler;
+use vulkano::pipeline::blend::{AttachmentBlend,BlendFactor};
+use vulkano::buffer::{BufferUsage,CpuAccessibleBuffer};
+
+/// Blending used for the interface. For transparent windows alpha is accumulated so that the
+/// output ends up premultiplied with the correct coverage for the window compositor.
+fn itf_blend(transparent: bool) -> AttachmentBlend {
+	if transparent {
+		AttachmentBlend {
+			alpha_source: BlendFactor::One,
+			.. AttachmentBlend::alpha_blending()
+		}
+	} else {
+		AttachmentBlend::alpha_blending()
+	}
+}
+
+/// Vertex buffer along with the image and sampler it is drawn with.
+pub(crate) type ItfDrawSet = (Arc<BufferAccess + Send + Sync>, Arc<ImageViewAccess + Send + Sync>, Arc<Sampler>);
 
 #[allow(dead_code)]
 struct RenderContext {
@@ -35,9 +55,19 @@ struct RenderContext {
 	clear_values: Vec<ClearValue>,
 }
 
+/// Pipeline used when drawing inside of a render pass owned by the application.
+struct SubpassContext {
+	subpass: Subpass<Arc<RenderPassAbstract + Send + Sync>>,
+	win_size: [u32; 2],
+	pipeline: Arc<GraphicsPipelineAbstract + Send + Sync>,
+	set_pool: FixedSizeDescriptorSetsPool<Arc<GraphicsPipelineAbstract + Send + Sync>>,
+}
+
 pub struct ItfRenderer {
 	basalt: Arc<Basalt>,
+	interface: Arc<Interface>,
 	rc_op: Option<RenderContext>,
+	sc_op: Option<SubpassContext>,
 	shader_vs: shaders::interface_vs::Shader,
 	shader_fs: shaders::interface_fs::Shader,
 	msaa: Mutex<u32>,
@@ -46,53 +76,181 @@ pub struct ItfRenderer {
 
 impl ItfRenderer {
 	pub fn new(basalt: Arc<Basalt>) -> Self {
+		let interface = basalt.interface();
+		Self::with_interface(basalt, interface)
+	}
+	
+	/// Renderer for an interface other than the one owned by ``Basalt``, such as a window's.
+	pub fn with_interface(basalt: Arc<Basalt>, interface: Arc<Interface>) -> Self {
 		let shader_vs = shaders::interface_vs::Shader::load(basalt.device.clone()).unwrap();
 		let shader_fs = shaders::interface_fs::Shader::load(basalt.device.clone()).unwrap();
 	
 		ItfRenderer {
+			interface,
 			rc_op: None,
+			sc_op: None,
 			msaa: Mutex::new(4),
 			scale: Mutex::new(1.0),
 			basalt, shader_vs, shader_fs
 		}
 	}
 	
+	/// The image the interface was last rendered to when not rendering to the swapchain.
+	pub fn target_image(&self) -> Option<Arc<ImageAccess + Send + Sync>> {
+		self.rc_op.as_ref().and_then(|rc| rc.target_op.as_ref().map(|v| v.0.clone()))
+	}
+	
 	/// Command buffer used must not be in the middle of a render pass. Resize is to be set to true
 	/// anytime the swapchain is recreated. Render to swapchain option will render the ui directly
 	/// onto the swapchain images. If this is not set this function will return ImageViewAccess to
-	/// the rendered image of the interface.
+	/// the rendered image of the interface and ``swap_imgs`` may be empty.
 	pub fn draw<S: Send + Sync + 'static>(
+		&mut self,
+		cmd: AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+		win_size: [u32; 2],
+		resize: bool,
+		swap_imgs: &Vec<Arc<SwapchainImage<S>>>,
+		render_to_swapchain: bool,
+		image_num: usize
+	) -> (AutoCommandBufferBuilder<StandardCommandPoolBuilder>, Option<Arc<ImageViewAccess + Send + Sync>>) {
+		self.draw_inner(cmd, win_size, resize, swap_imgs, render_to_swapchain, image_num, None)
+	}
+	
+	/// Draws the interface inside of the provided subpass. The command buffer must already be
+	/// in this subpass, it is left in it afterwards. The pipeline is recreated whenever the
+	/// subpass or ``win_size`` changes. Resize is to be set to true anytime the render target
+	/// is recreated. MSAA is determined by the subpass, so ``Interface::set_msaa()`` has no
+	/// effect here.
+	pub fn draw_in_subpass(
+		&mut self,
+		mut cmd: AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+		subpass: Subpass<Arc<RenderPassAbstract + Send + Sync>>,
+		win_size: [u32; 2],
+		mut resize: bool,
+	) -> AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
+		let mut scale = self.scale.lock();
+		
+		self.interface.itf_events.lock().retain(|e| match e {
+			ItfEvent::MSAAChanged => false,
+			ItfEvent::ScaleChanged => {
+				*scale = self.interface.scale();
+				resize = true;
+				false
+			}
+		});
+		
+		let recreate_sc = match self.sc_op.as_ref() {
+			Some(sc) => resize
+				|| sc.win_size != win_size
+				|| sc.subpass.index() != subpass.index()
+				|| !Arc::ptr_eq(sc.subpass.render_pass(), subpass.render_pass()),
+			None => true
+		};
+		
+		if recreate_sc {
+			let vert_input: Arc<SingleBufferDefinition<ItfVertInfo>> = Arc::new(SingleBufferDefinition::new());
+			let pipeline = Arc::new(
+				GraphicsPipeline::start()
+					.vertex_input(vert_input)
+					.vertex_shader(self.shader_vs.main_entry_point(), ())
+					.triangle_list()
+					.viewports(::std::iter::once(Viewport {
+						origin: [0.0, 0.0],
+						depth_range: 0.0 .. 1.0,
+						dimensions: [win_size[0] as f32, win_size[1] as f32],
+					}))
+					.fragment_shader(self.shader_fs.main_entry_point(), ())
+					.depth_stencil_disabled()
+					.blend_collective(itf_blend(self.basalt.options.transparent))
+					.render_pass(subpass.clone())
+					.polygon_mode_fill()
+					.build(self.basalt.device()).unwrap()
+			) as Arc<GraphicsPipelineAbstract + Send + Sync>;
+			
+			self.sc_op = Some(SubpassContext {
+				set_pool: FixedSizeDescriptorSetsPool::new(pipeline.clone(), 0),
+				subpass, win_size, pipeline
+			});
+		}
+		
+		let sc = self.sc_op.as_mut().unwrap();
+		
+		for (buf, buf_img, buf_sampler) in self.interface.odb.draw_data(win_size, resize, *scale) {
+			let set = sc.set_pool.next().add_sampled_image(buf_img, buf_sampler).unwrap().build().unwrap();
+			cmd = cmd.draw(sc.pipeline.clone(), &command_buffer::DynamicState::none(), vec![Arc::new(buf)], set, ()).unwrap();
+		}
+		
+		cmd
+	}
+	
+	/// Draws the provided sets instead of the whole interface into an offscreen image. Unlike
+	/// ``draw()`` this doesn't consume interface events, so it can be used alongside the
+	/// renderer used by the app loop.
+	pub(crate) fn draw_sets(
+		&mut self,
+		cmd: AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+		win_size: [u32; 2],
+		draw_sets: Vec<ItfDrawSet>,
+	) -> (AutoCommandBufferBuilder<StandardCommandPoolBuilder>, Option<Arc<ImageViewAccess + Send + Sync>>) {
+		let no_swap_imgs: Vec<Arc<SwapchainImage<()>>> = Vec::new();
+		self.draw_inner(cmd, win_size, false, &no_swap_imgs, false, 0, Some(draw_sets))
+	}
+	
+	fn draw_inner<S: Send + Sync + 'static>(
 		&mut self,
 		mut cmd: AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
 		win_size: [u32; 2],
 		mut resize: bool,
 		swap_imgs: &Vec<Arc<SwapchainImage<S>>>,
 		render_to_swapchain: bool,
-		image_num: usize
+		image_num: usize,
+		draw_sets: Option<Vec<ItfDrawSet>>,
 	) -> (AutoCommandBufferBuilder<StandardCommandPoolBuilder>, Option<Arc<ImageViewAccess + Send + Sync>>) {
-		const COLOR_FORMAT: VkFormat = VkFormat::R8G8B8A8Srgb;
+		let target_format: VkFormat = self.basalt.target_format();
 		let mut samples = self.msaa.lock();
 		let mut scale = self.scale.lock();
 		let mut recreate_rc = resize;
 		
-		self.basalt.interface_ref().itf_events.lock().retain(|e| match e {
-			ItfEvent::MSAAChanged => {
-				*samples = self.basalt.interface_ref().msaa();
+		if draw_sets.is_some() {
+			let msaa = self.interface.msaa();
+			
+			if *samples != msaa {
+				*samples = msaa;
 				recreate_rc = true;
-				false
-			}, ItfEvent::ScaleChanged => {
-				*scale = self.basalt.interface_ref().scale();
-				resize = true;
-				false
 			}
-		});
+			
+			if let Some(rc) = self.rc_op.as_ref() {
+				if rc.target_op.as_ref().map(|v| v.0.dimensions().width_height() != win_size).unwrap_or(true) {
+					recreate_rc = true;
+				}
+			}
+		} else {
+			if let Some(rc) = self.rc_op.as_ref() {
+				// Switching between rendering to the swapchain and offscreen needs a different context.
+				if rc.target_op.is_some() == render_to_swapchain {
+					recreate_rc = true;
+				}
+			}
+			
+			self.interface.itf_events.lock().retain(|e| match e {
+				ItfEvent::MSAAChanged => {
+					*samples = self.interface.msaa();
+					recreate_rc = true;
+					false
+				}, ItfEvent::ScaleChanged => {
+					*scale = self.interface.scale();
+					resize = true;
+					false
+				}
+			});
+		}
 		
 		if self.rc_op.is_none() || recreate_rc {
 			let target_op = if !render_to_swapchain {
 				Some(AttachmentImage::with_usage(
 					self.basalt.device(),
 					win_size,
-					COLOR_FORMAT,
+					target_format,
 					ImageUsage {
 						transfer_source: true,
 						color_attachment: true,
@@ -109,7 +267,7 @@ impl ItfRenderer {
 					self.basalt.device(),
 					win_size,
 					*samples,
-					COLOR_FORMAT,
+					target_format,
 					ImageUsage {
 						transfer_source: true,
 						color_attachment: true,
@@ -122,7 +280,7 @@ impl ItfRenderer {
 			};	
 			
 			let color_format = match render_to_swapchain {
-				false => COLOR_FORMAT,
+				false => target_format,
 				true => swap_imgs[0].swapchain().format()
 			};
 			
@@ -151,7 +309,7 @@ impl ItfRenderer {
 								image_ms: {
 									load: Clear,
 									store: Store,
-									format: COLOR_FORMAT,
+									format: target_format,
 									samples: s,
 								}, image: {
 									load: Clear,
@@ -173,12 +331,12 @@ impl ItfRenderer {
 								image_ms: {
 									load: Clear,
 									store: Store,
-									format: COLOR_FORMAT,
+									format: target_format,
 									samples: s,
 								}, image: {
 									load: Clear,
 									store: Store,
-									format: COLOR_FORMAT,
+									format: target_format,
 									samples: 1,
 								}
 							}, pass: {
@@ -191,8 +349,8 @@ impl ItfRenderer {
 				}
 			};
 			
-			let framebuffer = swap_imgs.iter().map(|image| {
-				if render_to_swapchain {
+			let framebuffer = if render_to_swapchain {
+				swap_imgs.iter().map(|image| {
 					if *samples > 1 {
 						Arc::new(Framebuffer::start(renderpass.clone())
 							.add(target_ms_op.as_ref().unwrap().clone()).unwrap()
@@ -205,21 +363,22 @@ impl ItfRenderer {
 							.build().unwrap()
 						) as Arc<vulkano::framebuffer::FramebufferAbstract + Send + Sync>
 					}
+				}).collect::<Vec<_>>()
+			} else {
+				// Every swapchain image would share the same target, so only one is needed.
+				vec![if *samples > 1 {
+					Arc::new(Framebuffer::start(renderpass.clone())
+						.add(target_ms_op.as_ref().unwrap().clone()).unwrap()
+						.add(target_op.as_ref().unwrap().clone()).unwrap()
+						.build().unwrap()
+					) as Arc<vulkano::framebuffer::FramebufferAbstract + Send + Sync>
 				} else {
-					if *samples > 1 {
-						Arc::new(Framebuffer::start(renderpass.clone())
-							.add(target_ms_op.as_ref().unwrap().clone()).unwrap()
-							.add(target_op.as_ref().unwrap().clone()).unwrap()
-							.build().unwrap()
-						) as Arc<vulkano::framebuffer::FramebufferAbstract + Send + Sync>
-					} else {
-						Arc::new(Framebuffer::start(renderpass.clone())
-							.add(target_op.as_ref().unwrap().clone()).unwrap()
-							.build().unwrap()
-						) as Arc<vulkano::framebuffer::FramebufferAbstract + Send + Sync>
-					}
-				}
-			}).collect::<Vec<_>>();
+					Arc::new(Framebuffer::start(renderpass.clone())
+						.add(target_op.as_ref().unwrap().clone()).unwrap()
+						.build().unwrap()
+					) as Arc<vulkano::framebuffer::FramebufferAbstract + Send + Sync>
+				}]
+			};
 			
 			let vert_input: Arc<SingleBufferDefinition<ItfVertInfo>> = Arc::new(SingleBufferDefinition::new());
 			let pipeline = Arc::new(
@@ -234,7 +393,7 @@ impl ItfRenderer {
 					}))
 					.fragment_shader(self.shader_fs.main_entry_point(), ())
 					.depth_stencil_disabled()
-					.blend_collective(vulkano::pipeline::blend::AttachmentBlend::alpha_blending())
+					.blend_collective(itf_blend(self.basalt.options.transparent))
 					.render_pass(Subpass::from(renderpass.clone(), 0).unwrap())
 					.polygon_mode_fill()
 					.build(self.basalt.device()).unwrap()
@@ -260,14 +419,155 @@ impl ItfRenderer {
 		}
 		
 		let rc = self.rc_op.as_mut().unwrap();
-		cmd = cmd.begin_render_pass(rc.framebuffer[image_num].clone(), false, rc.clear_values.clone()).unwrap();
+		let fb_i = if render_to_swapchain { image_num } else { 0 };
+		cmd = cmd.begin_render_pass(rc.framebuffer[fb_i].clone(), false, rc.clear_values.clone()).unwrap();
+		
+		let draw_sets = match draw_sets {
+			Some(some) => some,
+			None => self.interface.odb.draw_data(win_size, resize, *scale).into_iter()
+				.map(|(buf, buf_img, buf_sampler)| (Arc::new(buf) as Arc<BufferAccess + Send + Sync>, buf_img, buf_sampler))
+				.collect()
+		};
 		
-		for (buf, buf_img, buf_sampler) in self.basalt.interface_ref().odb.draw_data(win_size, resize, *scale) {
+		for (buf, buf_img, buf_sampler) in draw_sets {
 			let set = rc.set_pool.next().add_sampled_image(buf_img, buf_sampler).unwrap().build().unwrap();
-			cmd = cmd.draw(rc.pipeline.clone(), &command_buffer::DynamicState::none(), vec![Arc::new(buf)], set, ()).unwrap();
+			cmd = cmd.draw(rc.pipeline.clone(), &command_buffer::DynamicState::none(), vec![buf], set, ()).unwrap();
 		}
 		
 		cmd = cmd.end_render_pass().unwrap();
 		(cmd, rc.target_op.as_ref().map(|v| v.1.clone()))
 	}
 }
+
+#[derive(Default,Debug,Clone)]
+struct SquareVert {
+	position: [f32; 2],
+}
+
+impl_vertex!(SquareVert, position);
+
+struct CompositeContext {
+	format: VkFormat,
+	win_size: [u32; 2],
+	framebuffer: Vec<Arc<FramebufferAbstract + Send + Sync>>,
+	pipeline: Arc<GraphicsPipelineAbstract + Send + Sync>,
+	set_pool: FixedSizeDescriptorSetsPool<Arc<GraphicsPipelineAbstract + Send + Sync>>,
+}
+
+/// Draws an offscreen interface image on top of the existing contents of a swapchain image.
+pub(crate) struct ItfCompositor {
+	basalt: Arc<Basalt>,
+	cc_op: Option<CompositeContext>,
+	square_buf: Arc<CpuAccessibleBuffer<[SquareVert]>>,
+	sampler: Arc<Sampler>,
+	shader_vs: shaders::square_vs::Shader,
+	shader_fs: shaders::composite_fs::Shader,
+}
+
+impl ItfCompositor {
+	pub fn new(basalt: Arc<Basalt>) -> Self {
+		let shader_vs = shaders::square_vs::Shader::load(basalt.device.clone()).unwrap();
+		let shader_fs = shaders::composite_fs::Shader::load(basalt.device.clone()).unwrap();
+		let sampler = Sampler::simple_repeat_linear_no_mipmap(basalt.device());
+		
+		let square_buf = CpuAccessibleBuffer::from_iter(
+			basalt.device(),
+			BufferUsage::vertex_buffer(),
+			[
+				[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0],
+				[1.0, 1.0], [-1.0, 1.0], [-1.0, -1.0],
+			].iter().map(|position| SquareVert { position: *position })
+		).unwrap();
+		
+		ItfCompositor {
+			cc_op: None,
+			basalt, square_buf, sampler, shader_vs, shader_fs
+		}
+	}
+	
+	/// Command buffer used must not be in the middle of a render pass. Resize is to be set to
+	/// true anytime the swapchain is recreated.
+	pub fn draw<S: Send + Sync + 'static>(
+		&mut self,
+		mut cmd: AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+		itf_image: Arc<ImageViewAccess + Send + Sync>,
+		win_size: [u32; 2],
+		resize: bool,
+		swap_imgs: &Vec<Arc<SwapchainImage<S>>>,
+		image_num: usize
+	) -> AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
+		let format = swap_imgs[0].swapchain().format();
+		
+		let recreate_cc = match self.cc_op.as_ref() {
+			Some(cc) => resize
+				|| cc.format != format
+				|| cc.win_size != win_size
+				|| cc.framebuffer.len() != swap_imgs.len(),
+			None => true
+		};
+		
+		if recreate_cc {
+			let vert_input: Arc<SingleBufferDefinition<SquareVert>> = Arc::new(SingleBufferDefinition::new());
+			let renderpass = Arc::new(
+				single_pass_renderpass!(self.basalt.device(),
+					attachments: {
+						image: {
+							load: Load,
+							store: Store,
+							format: format,
+							samples: 1,
+						}
+					}, pass: {
+						color: [image],
+						depth_stencil: {},
+						resolve: []
+					}
+				).unwrap()
+			) as Arc<RenderPassAbstract + Send + Sync>;
+			
+			let framebuffer = swap_imgs.iter().map(|image| {
+				Arc::new(Framebuffer::start(renderpass.clone())
+					.add(image.clone()).unwrap()
+					.build().unwrap()
+				) as Arc<FramebufferAbstract + Send + Sync>
+			}).collect();
+			
+			let pipeline = Arc::new(
+				GraphicsPipeline::start()
+					.vertex_input(vert_input)
+					.vertex_shader(self.shader_vs.main_entry_point(), ())
+					.triangle_list()
+					.viewports(::std::iter::once(Viewport {
+						origin: [0.0, 0.0],
+						depth_range: 0.0 .. 1.0,
+						dimensions: [win_size[0] as f32, win_size[1] as f32],
+					}))
+					.fragment_shader(self.shader_fs.main_entry_point(), ())
+					.depth_stencil_disabled()
+					.blend_collective(AttachmentBlend {
+						color_source: BlendFactor::One,
+						alpha_source: BlendFactor::One,
+						.. AttachmentBlend::alpha_blending()
+					})
+					.render_pass(Subpass::from(renderpass.clone(), 0).unwrap())
+					.polygon_mode_fill()
+					.build(self.basalt.device()).unwrap()
+			) as Arc<GraphicsPipelineAbstract + Send + Sync>;
+			
+			self.cc_op = Some(CompositeContext {
+				set_pool: FixedSizeDescriptorSetsPool::new(pipeline.clone(), 0),
+				format, win_size, framebuffer, pipeline
+			});
+		}
+		
+		let cc = self.cc_op.as_mut().unwrap();
+		let set = cc.set_pool.next().add_sampled_image(itf_image, self.sampler.clone()).unwrap().build().unwrap();
+		
+		cmd = cmd
+			.begin_render_pass(cc.framebuffer[image_num].clone(), false, vec![ClearValue::None]).unwrap()
+			.draw(cc.pipeline.clone(), &command_buffer::DynamicState::none(), vec![self.square_buf.clone()], set, ()).unwrap()
+			.end_render_pass().unwrap();
+		
+		cmd
+	}
+}
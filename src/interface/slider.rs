@@ -4,7 +4,6 @@ use std::sync::atomic::{self,AtomicBool};
 use parking_lot::Mutex;
 use Basalt;
 use interface::TextWrap;
-use std::thread;
 use input::*;
 use std::time::Duration;
 
@@ -356,12 +355,12 @@ impl Slider {
 					
 						let funcs = _slider.on_change.lock().clone();
 						let at_copy = data.at.clone();
-		
-						thread::spawn(move || {
-							for func in funcs {
+						
+						_slider.basalt.scheduler_ref().set_timeout(Duration::from_millis(0), Arc::new(move || {
+							for func in &funcs {
 								func(at_copy);
 							}
-						});
+						}));
 					}
 				}
 				
@@ -445,10 +444,10 @@ impl Slider {
 		let funcs = self.on_change.lock().clone();
 		let at_copy = at.clone();
 		
-		thread::spawn(move || {
-			for func in funcs {
+		self.basalt.scheduler_ref().set_timeout(Duration::from_millis(0), Arc::new(move || {
+			for func in &funcs {
 				func(at_copy);
 			}
-		});
+		}));
 	}
 }
@@ -11,10 +11,11 @@ use std::ptr;
 use std::ffi::CString;
 use Basalt;
 use atlas;
+use error::{BasaltError,TextError};
 use crossbeam::channel::{self,Sender,Receiver};
 
 pub struct Text {
-	basalt: Arc<Basalt>,
+	atlas: Arc<atlas::Atlas>,
 	ft_faces: Mutex<BTreeMap<u32, (Arc<AtomicPtr<FT_LibraryRec>>, Arc<AtomicPtr<FT_FaceRec>>)>>,
 	hb_fonts: Mutex<BTreeMap<u32, Arc<AtomicPtr<hb_font_t>>>>,
 	size_infos: Mutex<BTreeMap<u32, SizeInfo>>,
@@ -70,7 +71,7 @@ impl Text {
 		let (hb_free_bufs_s, hb_free_bufs_r) = channel::unbounded();
 	
 		Arc::new(Text {
-			basalt,
+			atlas: basalt.atlas(),
 			ft_faces: Mutex::new(BTreeMap::new()),
 			hb_fonts: Mutex::new(BTreeMap::new()),
 			size_infos: Mutex::new(BTreeMap::new()),
@@ -84,7 +85,7 @@ impl Text {
 		&self, text: T, _family: F, size: u32, color: (f32, f32, f32, f32),
 		wrap: WrapTy, align: TextAlign,
 		line_height_op: Option<f32>, line_limit_op: Option<usize>
-	) -> Result<BTreeMap<u64, Vec<ItfVertInfo>>, String> {
+	) -> Result<BTreeMap<u64, Vec<ItfVertInfo>>, BasaltError> {
 		unsafe {
 			let hb_buffer_ap = match self.hb_free_bufs_r.try_recv() {
 				Ok(some) => some,
@@ -101,7 +102,7 @@ impl Text {
 
 					match FT_Init_FreeType(&mut ft_library) {
 						0 => (),
-						e => return Err(format!("FT_Init_FreeType: error {}", e))
+						e => return Err(TextError::FreeType { function: "FT_Init_FreeType", code: e as i32 }.into())
 					}
 					
 					let mut ft_face = ptr::null_mut();
@@ -109,12 +110,12 @@ impl Text {
 					
 					match FT_New_Memory_Face(ft_library, bytes.as_ptr(), (bytes.len() as i32).into(), 0, &mut ft_face) {
 						0 => (),
-						e => return Err(format!("FT_New_Memory_Face: error {}", e))
+						e => return Err(TextError::FreeType { function: "FT_New_Memory_Face", code: e as i32 }.into())
 					}
 					
 					match FT_Set_Pixel_Sizes(ft_face, 0, size.into()) {
 						0 => (),
-						e => return Err(format!("FT_Set_Pixel_Sizes: error {}", e))
+						e => return Err(TextError::FreeType { function: "FT_Set_Pixel_Sizes", code: e as i32 }.into())
 					}
 					
 					let ret = (Arc::new(AtomicPtr::new(ft_library)), Arc::new(AtomicPtr::new(ft_face)));
@@ -151,12 +152,12 @@ impl Text {
 					
 					let t_metrics = match FT_Load_Glyph(ft_face, info[0].codepoint.into(), FT_LOAD_DEFAULT as i32) {
 						0 => (*(*ft_face).glyph).metrics.clone(),
-						e => return Err(format!("FT_Load_Glyph: error {}", e))
+						e => return Err(TextError::FreeType { function: "FT_Load_Glyph", code: e as i32 }.into())
 					};
 					
 					let g_metrics = match FT_Load_Glyph(ft_face, info[1].codepoint.into(), FT_LOAD_DEFAULT as i32) {
 						0 => (*(*ft_face).glyph).metrics.clone(),
-						e => return Err(format!("FT_Load_Glyph: error {}", e))
+						e => return Err(TextError::FreeType { function: "FT_Load_Glyph", code: e as i32 }.into())
 					};
 					
 					let top = (pos[0].y_offset as f32 / 64.0) - (t_metrics.horiBearingY as f32 / 64.0);
@@ -201,12 +202,12 @@ impl Text {
 							
 							match FT_Load_Glyph(ft_face, info[i].codepoint.into(), FT_LOAD_DEFAULT as i32) {
 								0 => (),
-								e => return Err(format!("FT_Load_Glyph: error {}", e))
+								e => return Err(TextError::FreeType { function: "FT_Load_Glyph", code: e as i32 }.into())
 							}
 							
 							match FT_Render_Glyph((*ft_face).glyph, FT_RENDER_MODE_NORMAL) {
 								0 => (),
-								e => return Err(format!("FT_Render_Glyph: error {}", e))
+								e => return Err(TextError::FreeType { function: "FT_Render_Glyph", code: e as i32 }.into())
 							}
 							
 							let bitmap = &(*(*ft_face).glyph).bitmap;
@@ -231,7 +232,7 @@ impl Text {
 									image_data.push(*bitmap.buffer.offset(i));
 								}
 								
-								let coords = match self.basalt.atlas_ref().load_image(
+								let coords = match self.atlas.load_image(
 									atlas::SubImageCacheID::Glyph(size, info[i].codepoint as u64),
 									atlas::Image::new(
 										atlas::ImageType::Glyph,
@@ -243,7 +244,7 @@ impl Text {
 									).unwrap()
 								) {
 									Ok(ok) => ok,
-									Err(e) => return Err(format!("Atlas::load_raw_with_key: Error {}", e))
+									Err(e) => return Err(e)
 								};
 								
 								let ret = Arc::new(Glyph {
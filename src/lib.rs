@@ -14,6 +14,14 @@ extern crate num_cpus;
 extern crate image;
 extern crate decorum;
 extern crate freetype_sys;
+#[cfg(target_os = "linux")]
+extern crate x11_clipboard;
+#[macro_use]
+extern crate log;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate toml;
 
 pub mod interface;
 pub mod atlas;
@@ -21,20 +29,32 @@ pub mod misc;
 pub mod shaders;
 pub mod bindings;
 pub mod input;
+pub mod error;
+pub mod window;
+pub mod clipboard;
+pub mod scheduler;
+pub mod debug;
+pub mod logging;
+pub mod prefs;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 
 use atlas::Atlas;
 use interface::interface::Interface;
 use vulkano_win::{VkSurfaceBuild};
 use vulkano::sync::GpuFuture;
-use vulkano::instance::{Instance,PhysicalDevice};
-use vulkano::device::{self,Device,DeviceExtensions};
+use vulkano::instance::{Instance,InstanceExtensions,PhysicalDevice,PhysicalDeviceType};
+use vulkano::device::{self,Device,DeviceExtensions,RawDeviceExtensions,Features};
 use vulkano::swapchain::{self,Swapchain};
-use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::command_buffer::{AutoCommandBufferBuilder,CommandBuffer};
+use vulkano::command_buffer::pool::standard::StandardCommandPoolBuilder;
+use vulkano::image::swapchain::SwapchainImage;
+use vulkano::format::Format;
 use std::sync::Arc;
 use std::time::Instant;
-use parking_lot::{Mutex,RwLock};
+use parking_lot::{Mutex,Condvar};
 use std::sync::atomic::{self,AtomicBool,AtomicUsize};
-use std::collections::VecDeque;
+use std::collections::{VecDeque,BTreeMap};
 use std::thread;
 use std::sync::Barrier;
 use vulkano::swapchain::Surface;
@@ -42,8 +62,16 @@ use winit::Window;
 use std::thread::JoinHandle;
 use std::time::Duration;
 use input::Input;
+use clipboard::Clipboard;
+use scheduler::{Scheduler,TaskID,TaskRes};
+use misc::readback::Readback;
+use std::path::{Path,PathBuf};
+use error::{InitError,RenderError,WindowError};
+use window::{BasaltWindow,WindowOptions,WindowRequest};
+use prefs::PrefsStore;
+use crossbeam::queue::SegQueue;
 
-const SHOW_SWAPCHAIN_WARNINGS: bool = false;
+pub use error::BasaltError;
 
 #[derive(Debug)]
 pub struct Limits {
@@ -51,27 +79,191 @@ pub struct Limits {
 	pub max_image_dimension_3d: u32,
 }
 
+pub type BasaltEventHookID = u64;
+pub type RenderHookID = u64;
+/// Receives the command buffer, the swapchain image being rendered to and its dimensions.
+/// The command buffer is not in a render pass and must be returned outside of one.
+pub type RenderHookFn = Arc<Fn(
+	AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+	&Arc<SwapchainImage<Window>>,
+	[u32; 2]
+) -> AutoCommandBufferBuilder<StandardCommandPoolBuilder> + Send + Sync>;
+
+/// When a render hook is called in relation to the interface.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum RenderStage {
+	/// Before the interface is drawn. The interface is composited on top of what is drawn.
+	BeforeInterface,
+	/// After the interface is drawn.
+	AfterInterface,
+}
+
+#[derive(Debug,Clone,PartialEq)]
 pub enum BasaltEvent {
 	WindowResized,
 	DPIChanged(f32),
+	/// The interface scale changed. Includes the dpi factor unless ``ignore_dpi`` is set.
+	ScaleChanged(f32),
+	MSAAChanged(u32),
+	Focused,
+	LostFocus,
+	/// The window close button was pressed. Basalt will exit after this event.
+	CloseRequested,
+	SwapchainRecreated,
 }	
 
+/// Picks the first supported srgb format for a swapchain.
+fn swapchain_format(swap_caps: &swapchain::Capabilities) -> Result<Format, BasaltError> {
+	let preferred_swap_formats = vec![
+		Format::R8G8B8A8Srgb,
+		Format::B8G8R8A8Srgb,
+	];
+	
+	for a in &preferred_swap_formats {
+		for &(ref b, _) in &swap_caps.supported_formats {
+			if a == b {
+				return Ok(*a);
+			}
+		}
+	}
+	
+	Err(RenderError::NoSwapchainFormat(swap_caps.supported_formats.clone()).into())
+}
+
+/// The composite alpha mode for the swapchain. When transparent, modes that blend with what
+/// is behind the window are preferred, otherwise opaque is used when supported.
+fn composite_alpha(swap_caps: &swapchain::Capabilities, transparent: bool) -> Result<swapchain::CompositeAlpha, BasaltError> {
+	let supported = &swap_caps.supported_composite_alpha;
+	
+	if transparent {
+		if supported.pre_multiplied {
+			return Ok(swapchain::CompositeAlpha::PreMultiplied);
+		} else if supported.post_multiplied {
+			return Ok(swapchain::CompositeAlpha::PostMultiplied);
+		} else if supported.inherit {
+			return Ok(swapchain::CompositeAlpha::Inherit);
+		}
+	}
+	
+	if supported.opaque {
+		Ok(swapchain::CompositeAlpha::Opaque)
+	} else {
+		supported.iter().next().ok_or(RenderError::NoCompositeAlpha.into())
+	}
+}
+
+/// Extent to create the swapchain with. Some platforms such as wayland don't report an extent,
+/// the window's inner size is used instead.
+fn surface_extent(surface: &Surface<Window>, physical: PhysicalDevice) -> Result<[u32; 2], BasaltError> {
+	let swap_caps = surface.capabilities(physical).map_err(|e| InitError::SurfaceCapabilities(e))?;
+	
+	if let Some(extent) = swap_caps.current_extent {
+		return Ok(extent);
+	}
+	
+	let window = surface.window();
+	
+	let [w, h] = match window.get_inner_size() {
+		Some(size) => {
+			let size = size.to_physical(window.get_hidpi_factor());
+			[size.width.round() as u32, size.height.round() as u32]
+		}, None => swap_caps.min_image_extent
+	};
+	
+	Ok([
+		w.max(swap_caps.min_image_extent[0]).min(swap_caps.max_image_extent[0]),
+		h.max(swap_caps.min_image_extent[1]).min(swap_caps.max_image_extent[1])
+	])
+}
+
+/// Records the interface along with the render hooks, and the readbacks for the frame captures.
+/// Shared by the app loop and the render loops of additional windows. A failed readback loses
+/// the command buffer, in which case the captures fail with ``RenderError::FrameDropped``.
+fn record_frame(
+	basalt: &Arc<Basalt>,
+	mut cmd_buf: AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+	itf_renderer: &mut interface::render::ItfRenderer,
+	itf_compositor: &mut interface::render::ItfCompositor,
+	hooks: Vec<(RenderStage, RenderHookFn)>,
+	frame_captures: Vec<FrameCapture>,
+	win_size: [u32; 2],
+	resized: bool,
+	images: &Vec<Arc<SwapchainImage<Window>>>,
+	image_num: usize
+) -> Result<(AutoCommandBufferBuilder<StandardCommandPoolBuilder>, Vec<(FrameCapture, Readback)>), BasaltError> {
+	let mut before_hooks = Vec::new();
+	let mut after_hooks = Vec::new();
+	
+	for (stage, hook) in hooks {
+		match stage {
+			RenderStage::BeforeInterface => before_hooks.push(hook),
+			RenderStage::AfterInterface => after_hooks.push(hook),
+		}
+	}
+	
+	if before_hooks.is_empty() {
+		cmd_buf = itf_renderer.draw(cmd_buf, win_size, resized, images, true, image_num).0;
+	} else {
+		for hook in before_hooks {
+			cmd_buf = hook(cmd_buf, &images[image_num], win_size);
+		}
+		
+		// Drawing directly to the swapchain would clear what the hooks drew.
+		let (cmd, itf_image) = itf_renderer.draw(cmd_buf, win_size, resized, images, false, image_num);
+		cmd_buf = itf_compositor.draw(cmd, itf_image.unwrap(), win_size, resized, images, image_num);
+	}
+	
+	for hook in after_hooks {
+		cmd_buf = hook(cmd_buf, &images[image_num], win_size);
+	}
+	
+	let mut captures: Vec<(FrameCapture, Readback)> = Vec::new();
+	let mut frame_captures = frame_captures.into_iter();
+	
+	while let Some(capture) = frame_captures.next() {
+		let image = images[image_num].clone() as Arc<vulkano::image::ImageAccess + Send + Sync>;
+		
+		match Readback::new(basalt, &image) {
+			Ok(readback) => match readback.record(cmd_buf, image) {
+				Ok(ok) => {
+					cmd_buf = ok;
+					captures.push((capture, readback));
+				},
+				Err(e) => {
+					for capture in captures.into_iter().map(|(capture, _)| capture).chain(Some(capture)).chain(frame_captures) {
+						capture.complete(Err(RenderError::FrameDropped.into()));
+					}
+					
+					return Err(e);
+				}
+			},
+			Err(e) => capture.complete(Err(e))
+		}
+	}
+	
+	Ok((cmd_buf, captures))
+}
+
 struct Initials {
 	device: Arc<Device>,
 	graphics_queue: Arc<device::Queue>,
 	transfer_queue: Arc<device::Queue>,
-	surface: Arc<Surface<Window>>,
-	swap_caps: swapchain::Capabilities,
+	surface: Option<Arc<Surface<Window>>>,
+	swap_caps: Option<swapchain::Capabilities>,
 	limits: Arc<Limits>,
 	event_mk: Arc<Mutex<Option<Arc<Basalt>>>>,
 	event_mk_br: Arc<Barrier>,
 	pdevi: usize,
 	window_size: [u32; 2],
+	events_proxy: Option<winit::EventsLoopProxy>,
+	event_thread: Option<JoinHandle<()>>,
+	debug_reporter: Option<debug::Reporter>,
 }
 
 impl Initials {
-	pub fn use_first_device(options: Options) -> Result<Self, String> {
-		let mut device_num = 0;
+	/// Parses ``--use-device=N`` and ``--show-devices`` from the command line.
+	fn device_args() -> (Option<usize>, bool) {
+		let mut device_num = None;
 		let mut show_devices = false;
 		
 		for arg in ::std::env::args() {
@@ -79,24 +271,164 @@ impl Initials {
 				let split_by_eq: Vec<_> = arg.split("=").collect();
 				
 				if split_by_eq.len() < 2 {
-					println!("Incorrect '--use-device' usage. Example: '--use-device=2'");
+					warn!(target: logging::INIT, "Incorrect '--use-device' usage. Example: '--use-device=2'");
 					break;
 				} else {
 					device_num = match split_by_eq[1].parse() {
-						Ok(ok) => ok,
+						Ok(ok) => Some(ok),
 						Err(_) => {
-							println!("Incorrect '--use-device' usage. Example: '--use-device=2'");
+							warn!(target: logging::INIT, "Incorrect '--use-device' usage. Example: '--use-device=2'");
 							continue;
 						}
 					};
 					
-					println!("Using device: {}", device_num);
+					info!(target: logging::INIT, "Using device: {}", device_num.unwrap());
 				}
 			} else if arg.starts_with("--show-devices") {
 				show_devices = true;
 			}
 		}
+		
+		(device_num, show_devices)
+	}
+	
+	fn select_physical<'a>(instance: &'a Arc<Instance>, options: &Options) -> Result<PhysicalDevice<'a>, BasaltError> {
+		let physical_devs: Vec<_> = PhysicalDevice::enumerate(instance).collect();
+		let mut selector = options.device.clone();
+		
+		if options.parse_args {
+			let (device_num, show_devices) = Self::device_args();
+			
+			if show_devices {
+				info!(target: logging::INIT, "Devices:");
+				
+				for (i, dev) in physical_devs.iter().enumerate() {
+					info!(target: logging::INIT, "  {}: {} ({:?})", i, dev.name(), dev.ty());
+				}
+			}
+			
+			if let Some(device_num) = device_num {
+				selector = DeviceSelector::Index(device_num);
+			}
+		}
+		
+		if physical_devs.is_empty() {
+			return Err(InitError::NoPhysicalDevices.into());
+		}
+		
+		match selector {
+			DeviceSelector::Index(i) => match physical_devs.get(i) {
+				Some(some) => Ok(*some),
+				None => Err(InitError::PhysicalDeviceNotFound(i).into())
+			},
+			DeviceSelector::Name(name) => {
+				let lowercase = name.to_lowercase();
+				
+				match physical_devs.iter().find(|dev| dev.name().to_lowercase().contains(&lowercase)) {
+					Some(some) => Ok(*some),
+					None => Err(InitError::NoDeviceNamed(name).into())
+				}
+			},
+			DeviceSelector::Type(types) => {
+				for ty in &types {
+					if let Some(some) = physical_devs.iter().find(|dev| dev.ty() == *ty) {
+						return Ok(*some);
+					}
+				}
+				
+				Err(InitError::NoDeviceOfType(types).into())
+			},
+			DeviceSelector::Custom(func) => match physical_devs.iter().find(|dev| func(dev)) {
+				Some(some) => Ok(*some),
+				None => Err(InitError::NoDeviceAccepted.into())
+			}
+		}
+	}
+	
+	/// Creates the logical device along with the graphics and transfer queues. When a surface
+	/// is provided the graphics queue must also be able to present to it. Every supported
+	/// feature is enabled, along with ``device_ext`` and the extensions from the options.
+	fn create_device(
+		physical: PhysicalDevice,
+		surface: Option<&Arc<Surface<Window>>>,
+		device_ext: DeviceExtensions,
+		options: &Options,
+	) -> Result<(Arc<Device>, Arc<device::Queue>, Arc<device::Queue>), BasaltError> {
+		if !physical.supported_features().superset_of(&options.device_features) {
+			return Err(InitError::MissingFeatures(options.device_features.difference(physical.supported_features())).into());
+		}
+		
+		let device_ext = RawDeviceExtensions::from(&device_ext).union(&RawDeviceExtensions::from(&options.device_ext));
+		let mut queue_family_opts = Vec::new();
+	
+		for family in physical.queue_families() {
+			for _ in 0..family.queues_count() {
+				queue_family_opts.push(family);
+			}
+		}
+		
+		let mut graphics_queue_ = None;
+		let mut transfer_queue_ = None;
+		
+		for i in 0..queue_family_opts.len() {
+			if
+				queue_family_opts[i].supports_graphics() &&
+				surface.map(|s| s.is_supported(queue_family_opts[i]).unwrap_or(false)).unwrap_or(true)
+			{	
+				graphics_queue_ = Some((queue_family_opts[i], 0.8));
+				queue_family_opts.remove(i);
+				break;
+			}
+		} if graphics_queue_.is_none() {
+			return Err(InitError::NoGraphicsQueue.into());
+		}
+		
+		for i in 0..queue_family_opts.len() {
+			transfer_queue_ = Some((queue_family_opts[i], 0.2));
+			queue_family_opts.remove(i);
+			break;
+		} if transfer_queue_.is_none() {
+			info!(target: logging::INIT, "Couldn't find a suitable queue for transfers, using the graphics queue for transfers also.");
+		}
+		
+		let mut req_queues = Vec::new();
+		req_queues.push(graphics_queue_.unwrap());
+		
+		if let Some(transfer_queue) = transfer_queue_ {
+			req_queues.push(transfer_queue);
+		}
+		
+		let (device, mut queues) = match Device::new(
+			physical, physical.supported_features(), 
+			device_ext, req_queues)
+		{
+			Ok(ok) => ok,
+			Err(e) => return Err(InitError::CreateDevice(e).into())
+		}; let graphics_queue = match queues.next() {
+			Some(some) => some,
+			None => return Err(InitError::NoQueues.into())
+		}; let transfer_queue = match queues.next() {
+			Some(some) => some,
+			None => graphics_queue.clone()
+		};
+		
+		Ok((device, graphics_queue, transfer_queue))
+	}
 	
+	fn limits(physical: &PhysicalDevice) -> Limits {
+		let phy_limits = physical.limits();
+		
+		Limits {
+			max_image_dimension_2d: phy_limits.max_image_dimension_2d(),
+			max_image_dimension_3d: phy_limits.max_image_dimension_3d(),
+		}
+	}
+	
+	pub fn use_first_device(options: Options) -> Result<Self, BasaltError> {
+		if options.headless.is_some() {
+			return Self::headless(&options);
+		}
+		
 		let extensions = vulkano_win::required_extensions();
 		let device_ext = DeviceExtensions { khr_swapchain: true, .. DeviceExtensions::none() };
 		
@@ -110,42 +442,22 @@ impl Initials {
 		let event_mk_br = Arc::new(Barrier::new(2));
 		let event_mk_br_copy = event_mk_br.clone();
 		
-		thread::spawn(move || {
+		let event_thread = thread::spawn(move || {
 			let mut events_loop = winit::EventsLoop::new();
+			let events_proxy = events_loop.create_proxy();
 			
-			*window_result_copy.lock() = Some((|| -> _ {
-				let instance = match Instance::new(None, &extensions, None) {
-					Ok(ok) => ok,
-					Err(e) => return Err(format!("Failed to create instance: {}", e))
-				};
-				
-				let mut physical_devs: Vec<_> = PhysicalDevice::enumerate(&instance).collect();
-				
-				if show_devices {
-					println!("Devices:");
-					for (i, dev) in physical_devs.iter().enumerate() {
-						println!("  {}: {}", i, dev.name());
-					}
-				}
-				
-				match physical_devs.get(device_num) {
-					Some(_) => (),
-					None => if device_num == 0 {
-						return Err(format!("No physical devices available."))
-					} else {
-						return Err(format!("Phyiscal device not found."))
-					}
-				};
-				
-				let physical = physical_devs.remove(device_num);
+			*window_result_copy.lock() = Some((|| -> Result<Self, BasaltError> {
+				let (instance, debug_reporter) = debug::create_instance(extensions, &options.debug)?;
+				let physical = Self::select_physical(&instance, &options)?;
 				
 				let surface = match winit::WindowBuilder::new()
 					.with_dimensions((800, 400).into())
 					.with_title(options.title.clone())
+					.with_transparency(options.transparent)
 					.build_vk_surface(&events_loop, instance.clone())
 				{
 					Ok(ok) => ok,
-					Err(e) => return Err(format!("Failed to build window: {}", e))
+					Err(e) => return Err(InitError::CreateWindow(e).into())
 				};
 				
 				let window_size;
@@ -170,94 +482,173 @@ impl Initials {
 					)
 				});
 				
-				let mut queue_family_opts = Vec::new();
-			
-				for family in physical.queue_families() {
-					for _ in 0..family.queues_count() {
-						queue_family_opts.push(family);
-					}
-				}
-				
-				let mut graphics_queue_ = None;
-				let mut transfer_queue_ = None;
-				
-				for i in 0..queue_family_opts.len() {
-					if
-						queue_family_opts[i].supports_graphics() &&
-						surface.is_supported(queue_family_opts[i]).unwrap_or(false)
-					{	
-						graphics_queue_ = Some((queue_family_opts[i], 0.8));
-						queue_family_opts.remove(i);
-						break;
-					}
-				} if graphics_queue_.is_none() {
-					return Err(format!("Couldn't find a suitable queue for graphics."));
-				}
-				
-				for i in 0..queue_family_opts.len() {
-					transfer_queue_ = Some((queue_family_opts[i], 0.2));
-					queue_family_opts.remove(i);
-					break;
-				} if transfer_queue_.is_none() {
-					println!("Couldn't find a suitable queue for transfers.\
-						\nUsing graphics queue for transfers also.");
-				}
-				
-				let mut req_queues = Vec::new();
-				req_queues.push(graphics_queue_.unwrap());
+				let (device, graphics_queue, transfer_queue) = Self::create_device(physical, Some(&surface), device_ext, &options)?;
 				
-				if let Some(transfer_queue) = transfer_queue_ {
-					req_queues.push(transfer_queue);
-				}
-				
-				let (device, mut queues) = match Device::new(
-					physical, physical.supported_features(), 
-					&device_ext, req_queues)
-				{
+				let swap_caps = match surface.capabilities(physical) {
 					Ok(ok) => ok,
-					Err(e) => return Err(format!("Failed to create device: {}", e))
-				}; let graphics_queue = match queues.next() {
-					Some(some) => some,
-					None => return Err(format!("Device didn't have any queues"))
-				}; let transfer_queue = match queues.next() {
-					Some(some) => some,
-					None => graphics_queue.clone()
-				}; let swap_caps = match surface.capabilities(physical) {
-					Ok(ok) => ok,
-					Err(e) => return Err(format!("Failed to get surface capabilities: {}", e))
-				};
-				
-				let phy_limits = physical.limits();
-				
-				let limits = Limits {
-					max_image_dimension_2d: phy_limits.max_image_dimension_2d(),
-					max_image_dimension_3d: phy_limits.max_image_dimension_3d(),
+					Err(e) => return Err(InitError::SurfaceCapabilities(e).into())
 				};
 				
 				Ok(Initials {
 					device: device,
 					graphics_queue: graphics_queue,
 					transfer_queue: transfer_queue,
-					surface: surface.clone(),
-					swap_caps: swap_caps,
-					limits: Arc::new(limits),
+					surface: Some(surface.clone()),
+					swap_caps: Some(swap_caps),
+					limits: Arc::new(Self::limits(&physical)),
 					event_mk: event_mk,
 					event_mk_br: event_mk_br,
-					pdevi: device_num,
+					pdevi: physical.index(),
 					window_size,
+					events_proxy: Some(events_proxy),
+					event_thread: None,
+					debug_reporter,
 				})
 			})());
 			
+			let created = window_result_copy.lock().as_ref().map(|r| r.is_ok()).unwrap_or(false);
 			window_res_barrier_copy.wait();
-			event_mk_br_copy.wait();
 			
+			// Basalt is never created on failure, so there is nothing to wait for.
+			if !created {
+				return;
+			}
+			
+			event_mk_br_copy.wait();
 			let basalt = event_mk_copy.lock().take().unwrap();
-			input::winit::run(basalt.clone(), &mut events_loop);
+			input::winit::run(basalt, &mut events_loop);
 		});
 		
 		window_res_barrier.wait();
 		let mut window_result_op = window_result.lock();
-		window_result_op.take().unwrap()
+		
+		window_result_op.take().unwrap().map(|mut initials| {
+			initials.event_thread = Some(event_thread);
+			initials
+		})
+	}
+	
+	/// Creates a device without a surface. No window or event loop is created.
+	fn headless(options: &Options) -> Result<Self, BasaltError> {
+		let (instance, debug_reporter) = debug::create_instance(InstanceExtensions::none(), &options.debug)?;
+		let physical = Self::select_physical(&instance, options)?;
+		let (device, graphics_queue, transfer_queue) = Self::create_device(physical, None, DeviceExtensions::none(), options)?;
+		
+		Ok(Initials {
+			device: device,
+			graphics_queue: graphics_queue,
+			transfer_queue: transfer_queue,
+			surface: None,
+			swap_caps: None,
+			limits: Arc::new(Self::limits(&physical)),
+			event_mk: Arc::new(Mutex::new(None)),
+			event_mk_br: Arc::new(Barrier::new(1)),
+			pdevi: physical.index(),
+			window_size: options.window_size,
+			events_proxy: None,
+			event_thread: None,
+			debug_reporter,
+		})
+	}
+	
+	/// Uses a device created by the application. No window or event loop is created.
+	fn from_existing(
+		device: Arc<Device>,
+		graphics_queue: Arc<device::Queue>,
+		transfer_queue: Option<Arc<device::Queue>>,
+		options: &Options
+	) -> Self {
+		let physical = device.physical_device();
+		
+		Initials {
+			transfer_queue: transfer_queue.unwrap_or_else(|| graphics_queue.clone()),
+			device: device.clone(),
+			graphics_queue: graphics_queue,
+			surface: None,
+			swap_caps: None,
+			limits: Arc::new(Self::limits(&physical)),
+			event_mk: Arc::new(Mutex::new(None)),
+			event_mk_br: Arc::new(Barrier::new(1)),
+			pdevi: physical.index(),
+			window_size: options.window_size,
+			events_proxy: None,
+			event_thread: None,
+			debug_reporter: None,
+		}
+	}
+}
+
+/// Selects which physical device Basalt will use.
+#[derive(Clone)]
+pub enum DeviceSelector {
+	/// Index into the list returned by ``Basalt::enumerate_devices()``.
+	Index(usize),
+	/// First device whose name contains this string. Case insensitive.
+	Name(String),
+	/// First device matching a type, checked in the order given.
+	Type(Vec<PhysicalDeviceType>),
+	/// First device the closure returns true for.
+	Custom(Arc<Fn(&PhysicalDevice) -> bool + Send + Sync>),
+}
+
+impl ::std::fmt::Debug for DeviceSelector {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		match self {
+			DeviceSelector::Index(i) => write!(f, "DeviceSelector::Index({})", i),
+			DeviceSelector::Name(name) => write!(f, "DeviceSelector::Name({:?})", name),
+			DeviceSelector::Type(types) => write!(f, "DeviceSelector::Type({:?})", types),
+			DeviceSelector::Custom(_) => write!(f, "DeviceSelector::Custom(..)"),
+		}
+	}
+}
+
+#[derive(Debug,Clone)]
+pub struct DeviceInfo {
+	pub index: usize,
+	pub name: String,
+	pub ty: PhysicalDeviceType,
+}
+
+/// Swapchain present mode. If the requested mode isn't supported the closest supported mode
+/// is used instead, ending with ``Fifo`` which is always available.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum PresentMode {
+	/// Vsync.
+	Fifo,
+	/// Vsync, but late frames are presented immediately. Falls back to ``Fifo``.
+	Relaxed,
+	/// No tearing without waiting on vblank. Falls back to ``Immediate`` then ``Fifo``.
+	Mailbox,
+	/// No vsync. Falls back to ``Mailbox`` then ``Fifo``.
+	Immediate,
+}
+
+impl PresentMode {
+	fn select(self, supported: &swapchain::SupportedPresentModes) -> swapchain::PresentMode {
+		let order: &[PresentMode] = match self {
+			PresentMode::Fifo => &[PresentMode::Fifo],
+			PresentMode::Relaxed => &[PresentMode::Relaxed, PresentMode::Fifo],
+			PresentMode::Mailbox => &[PresentMode::Mailbox, PresentMode::Immediate, PresentMode::Fifo],
+			PresentMode::Immediate => &[PresentMode::Immediate, PresentMode::Mailbox, PresentMode::Fifo],
+		};
+		
+		for mode in order {
+			match mode {
+				PresentMode::Fifo => if supported.fifo { return swapchain::PresentMode::Fifo },
+				PresentMode::Relaxed => if supported.relaxed { return swapchain::PresentMode::Relaxed },
+				PresentMode::Mailbox => if supported.mailbox { return swapchain::PresentMode::Mailbox },
+				PresentMode::Immediate => if supported.immediate { return swapchain::PresentMode::Immediate },
+			}
+		}
+		
+		swapchain::PresentMode::Fifo
+	}
+	
+	fn is_vsync(&self) -> bool {
+		match self {
+			PresentMode::Fifo | PresentMode::Relaxed => true,
+			PresentMode::Mailbox | PresentMode::Immediate => false,
+		}
 	}
 }
 
@@ -275,6 +666,19 @@ pub struct Options {
 	title: String,
 	scale: f32,
 	input_src: InputSource,
+	headless: Option<[u32; 2]>,
+	device: DeviceSelector,
+	parse_args: bool,
+	present_mode: PresentMode,
+	frame_limit: Option<u32>,
+	on_demand: bool,
+	transparent: bool,
+	debug: debug::DebugOptions,
+	device_ext: DeviceExtensions,
+	device_features: Features,
+	prefs: bool,
+	prefs_path: Option<PathBuf>,
+	scale_hotkeys: Option<ScaleHotkeys>,
 }
 
 impl Default for Options {
@@ -285,6 +689,19 @@ impl Default for Options {
 			title: "vk-basalt".to_string(),
 			scale: 1.0,
 			input_src: InputSource::Winit,
+			headless: None,
+			device: DeviceSelector::Index(0),
+			parse_args: false,
+			present_mode: PresentMode::Relaxed,
+			frame_limit: None,
+			on_demand: false,
+			transparent: false,
+			debug: debug::DebugOptions::default(),
+			device_ext: DeviceExtensions::none(),
+			device_features: Features::none(),
+			prefs: false,
+			prefs_path: None,
+			scale_hotkeys: Some(ScaleHotkeys::default()),
 		}
 	}
 }
@@ -309,6 +726,185 @@ impl Options {
 		self.scale = to;
 		self
 	}
+	
+	/// Run without a window. The interface is rendered into an offscreen image of the
+	/// provided size. The latest frame can be retrieved with ``Basalt::headless_frame()``.
+	pub fn headless(mut self, width: u32, height: u32) -> Self {
+		self.headless = Some([width, height]);
+		self.window_size = [width, height];
+		self.ignore_dpi = true;
+		self
+	}
+	
+	/// Set how the physical device is selected. Defaults to the first device.
+	pub fn device(mut self, selector: DeviceSelector) -> Self {
+		self.device = selector;
+		self
+	}
+	
+	/// Parse ``--use-device=N`` and ``--show-devices`` from the command line. A device
+	/// given on the command line takes priority over the one set with ``device()``.
+	pub fn parse_args(mut self, to: bool) -> Self {
+		self.parse_args = to;
+		self
+	}
+	
+	/// Set the present mode used by the swapchain. Defaults to ``PresentMode::Relaxed``.
+	pub fn present_mode(mut self, mode: PresentMode) -> Self {
+		self.present_mode = mode;
+		self
+	}
+	
+	/// Limit the amount of frames rendered per second. Defaults to no limit.
+	pub fn frame_limit(mut self, fps: Option<u32>) -> Self {
+		self.frame_limit = fps;
+		self
+	}
+	
+	/// Only render a new frame when the interface changes or ``Basalt::request_redraw()``
+	/// is called. Frame callbacks added with ``Scheduler::on_frame()`` are only called per frame, so
+	/// they will not run while idle.
+	pub fn on_demand(mut self, to: bool) -> Self {
+		self.on_demand = to;
+		self
+	}
+	
+	/// Request a transparent window. Areas not covered by bins are see-through. The interface
+	/// is output with premultiplied alpha, so compositors only supporting post-multiplied
+	/// alpha will show darker edges on translucent bins.
+	pub fn transparent(mut self, to: bool) -> Self {
+		self.transparent = to;
+		self
+	}
+	
+	/// Enable the Khronos validation layer and debug reporting if they are available. A
+	/// warning is logged if they aren't. Defaults to false.
+	pub fn validation(mut self, to: bool) -> Self {
+		self.debug.validation = to;
+		self
+	}
+	
+	/// The least severe debug message that is reported. Defaults to ``DebugSeverity::Warning``.
+	pub fn debug_severity(mut self, severity: debug::DebugSeverity) -> Self {
+		self.debug.severity = severity;
+		self
+	}
+	
+	/// Receive debug messages instead of having them logged. Only used with ``validation()``.
+	/// The callback may be called from any thread while Vulkan functions are being called.
+	pub fn debug_callback(mut self, func: debug::DebugCallbackFn) -> Self {
+		self.debug.callback = Some(func);
+		self
+	}
+	
+	/// Extra device extensions to enable, in addition to the ones basalt needs. Ignored by
+	/// ``Basalt::from_existing()``.
+	pub fn device_extensions(mut self, ext: DeviceExtensions) -> Self {
+		self.device_ext = ext;
+		self
+	}
+	
+	/// Features the device is required to support. Every supported feature is already enabled,
+	/// so this only makes creation fail early when one is missing. Ignored by
+	/// ``Basalt::from_existing()``.
+	pub fn device_features(mut self, features: Features) -> Self {
+		self.device_features = features;
+		self
+	}
+	
+	/// Restore the scale, msaa, window size, position and fullscreen state from the last run
+	/// and save them on exit. The file is named after the title, see ``prefs`` for where it
+	/// is stored. Defaults to false.
+	pub fn prefs(mut self, to: bool) -> Self {
+		self.prefs = to;
+		self
+	}
+	
+	/// Same as ``prefs(true)``, but using the file at ``path``.
+	pub fn prefs_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+		self.prefs = true;
+		self.prefs_path = Some(path.into());
+		self
+	}
+	
+	/// Keys used to change the interface scale. ``None`` disables them. Defaults to
+	/// ``LCtrl + Equal`` to increase and ``LCtrl + Dash`` to decrease.
+	///
+	/// These and the other built-in hotkeys (F1 help, F2 fps, F7/F8 msaa and F10 vsync)
+	/// report through ``info!`` on the ``logging`` targets, so their output is only
+	/// visible when the application installs a logger.
+	pub fn scale_hotkeys(mut self, hotkeys: Option<ScaleHotkeys>) -> Self {
+		self.scale_hotkeys = hotkeys;
+		self
+	}
+	
+	fn open_prefs(&self) -> Option<PrefsStore> {
+		if !self.prefs {
+			return None;
+		}
+		
+		match self.prefs_path.clone().or_else(|| prefs::config_dir().map(|dir| dir.join(prefs::file_name(&self.title)))) {
+			Some(path) => Some(PrefsStore::open(path)),
+			None => {
+				warn!(target: logging::INIT, "Unable to find the config directory, preferences will not be saved.");
+				None
+			}
+		}
+	}
+}
+
+/// Keys for the built-in hotkeys that change the interface scale.
+#[derive(Debug,Clone,PartialEq)]
+pub struct ScaleHotkeys {
+	pub increase: Vec<input::Qwery>,
+	pub decrease: Vec<input::Qwery>,
+}
+
+impl Default for ScaleHotkeys {
+	fn default() -> Self {
+		ScaleHotkeys {
+			increase: vec![input::Qwery::LCtrl, input::Qwery::Equal],
+			decrease: vec![input::Qwery::LCtrl, input::Qwery::Dash],
+		}
+	}
+}
+
+impl ScaleHotkeys {
+	fn describe(keys: &[input::Qwery]) -> String {
+		keys.iter().map(|key| format!("{:?}", key)).collect::<Vec<_>>().join(" + ")
+	}
+}
+
+#[derive(Clone)]
+pub(crate) struct FrameCapture {
+	result: Arc<Mutex<Option<Result<image::RgbaImage, BasaltError>>>>,
+	condvar: Arc<Condvar>,
+}
+
+impl FrameCapture {
+	pub(crate) fn new() -> Self {
+		FrameCapture {
+			result: Arc::new(Mutex::new(None)),
+			condvar: Arc::new(Condvar::new()),
+		}
+	}
+	
+	pub(crate) fn complete(&self, result: Result<image::RgbaImage, BasaltError>) {
+		let mut lock = self.result.lock();
+		*lock = Some(result);
+		self.condvar.notify_one();
+	}
+	
+	/// Blocks until ``complete()`` has been called.
+	pub(crate) fn wait(&self) -> Result<image::RgbaImage, BasaltError> {
+		let mut result = self.result.lock();
+		
+		while result.is_none() {
+			self.condvar.wait(&mut result);
+		}
+		
+		result.take().unwrap()
+	}
 }
 
 pub enum ResizeTo {
@@ -316,52 +912,158 @@ pub enum ResizeTo {
 	FullScreen(bool),
 }
 
+/// Changes to the window applied by the app loop.
+enum WindowCmd {
+	Title(String),
+	Maximized(bool),
+	Position(i32, i32),
+	MinSize(Option<[u32; 2]>),
+	MaxSize(Option<[u32; 2]>),
+	Decorations(bool),
+	AlwaysOnTop(bool),
+	Icon(Option<winit::Icon>),
+	FullScreenOn(winit::MonitorId),
+}
+
+/// A monitor as returned by ``Basalt::monitors()``. Position and size are physical.
+#[derive(Debug,Clone,PartialEq)]
+pub struct MonitorInfo {
+	pub index: usize,
+	pub name: Option<String>,
+	pub position: [i32; 2],
+	pub size: [u32; 2],
+	pub hidpi_factor: f64,
+}
+
 #[allow(dead_code)]
 pub struct Basalt {
 	device: Arc<Device>,
 	graphics_queue: Arc<device::Queue>,
 	transfer_queue: Arc<device::Queue>,
-	surface: Arc<Surface<Window>>,
-	swap_caps: swapchain::Capabilities,
-	do_every: RwLock<Vec<Arc<Fn() + Send + Sync>>>,
+	surface: Option<Arc<Surface<Window>>>,
+	swap_caps: Option<swapchain::Capabilities>,
+	scheduler: Arc<Scheduler>,
 	mouse_capture: AtomicBool,
 	allow_mouse_cap: AtomicBool,
 	fps: AtomicUsize,
 	interface: Arc<Interface>,
 	atlas: Arc<Atlas>,
 	input: Arc<Input>,
+	clipboard: Arc<Clipboard>,
 	wants_exit: AtomicBool,
 	force_resize: AtomicBool,
 	#[allow(dead_code)]
 	limits: Arc<Limits>,
 	resize_requested: AtomicBool,
 	resize_to: Mutex<Option<ResizeTo>>,
-	loop_thread: Mutex<Option<JoinHandle<Result<(), String>>>>,
+	window_cmds: Mutex<Vec<WindowCmd>>,
+	loop_thread: Mutex<Option<JoinHandle<Result<(), BasaltError>>>>,
 	pdevi: usize,
-	vsync: Mutex<bool>,
+	present_mode: Mutex<PresentMode>,
+	frame_limit: Mutex<Option<u32>>,
+	frame_times: Mutex<VecDeque<f32>>,
+	/// Incremented for every redraw request, each render loop keeps the last one it has seen.
+	redraw: Mutex<u64>,
+	redraw_cond: Condvar,
 	wait_on_futures: Mutex<Vec<(Box<GpuFuture + Send + Sync>, Arc<Barrier>)>>,
 	window_size: Mutex<[u32; 2]>,
 	custom_scale: Mutex<f32>,
 	options: Options,
 	ignore_dpi_data: Mutex<Option<(usize, Instant, u32, u32)>>,
+	headless_frame: Mutex<Option<Vec<u8>>>,
+	frame_captures: Mutex<Vec<FrameCapture>>,
+	target_format: Format,
+	event_hooks: Mutex<BTreeMap<BasaltEventHookID, Arc<Fn(&BasaltEvent) + Send + Sync>>>,
+	event_hook_id: AtomicUsize,
+	render_hooks: Mutex<BTreeMap<RenderHookID, (RenderStage, RenderHookFn)>>,
+	render_hook_id: AtomicUsize,
+	events_proxy: Mutex<Option<winit::EventsLoopProxy>>,
+	windows: Mutex<Vec<Arc<BasaltWindow>>>,
+	window_requests: SegQueue<WindowRequest>,
+	closed_surfaces: SegQueue<Arc<Surface<Window>>>,
+	threads: Mutex<Vec<JoinHandle<()>>>,
+	on_exit: Mutex<Vec<Arc<Fn() + Send + Sync>>>,
+	shut_down: AtomicBool,
+	debug_reporter: Option<debug::Reporter>,
+	warnings: logging::Warnings,
+	prefs: Option<PrefsStore>,
+	fullscreen: AtomicBool,
 }
 
 #[allow(dead_code)]
 impl Basalt {
-	pub fn new(options: Options) -> Result<Arc<Self>, String> {
-		unsafe {
-			let initials = match Initials::use_first_device(options.clone()) {
-				Ok(ok) => ok,
-				Err(e) => return Err(e)
-			};
+	/// List the physical devices available for use with ``Options::device()``.
+	pub fn enumerate_devices() -> Result<Vec<DeviceInfo>, BasaltError> {
+		let instance = match Instance::new(None, &InstanceExtensions::none(), None) {
+			Ok(ok) => ok,
+			Err(e) => return Err(InitError::CreateInstance(e).into())
+		};
+		
+		Ok(PhysicalDevice::enumerate(&instance).map(|dev| DeviceInfo {
+			index: dev.index(),
+			name: dev.name(),
+			ty: dev.ty(),
+		}).collect())
+	}
+	
+	pub fn new(mut options: Options) -> Result<Arc<Self>, BasaltError> {
+		let prefs = options.open_prefs();
+		
+		if let Some(prefs) = prefs.as_ref().map(|prefs| prefs.get()) {
+			if let Some(scale) = prefs.scale {
+				options.scale = scale;
+			}
 			
+			if let Some(size) = prefs.window_size {
+				if options.headless.is_none() {
+					options.window_size = size;
+				}
+			}
+		}
+		
+		let initials = match Initials::use_first_device(options.clone()) {
+			Ok(ok) => ok,
+			Err(e) => return Err(e)
+		};
+		
+		Ok(Self::with_initials(initials, options, Format::R8G8B8A8Srgb, prefs))
+	}
+	
+	/// Create the interface using a device and queues owned by the application. No window,
+	/// swapchain or event loop is created. Input has to be provided through
+	/// ``input_ref().send_event()`` and the window size kept up to date with
+	/// ``set_window_size()``. The interface can then be drawn inside of the application's own
+	/// render pass with ``ItfRenderer::draw_in_subpass()``, or into an offscreen image of
+	/// ``format`` with ``ItfRenderer::draw()``. If ``transfer_queue`` is ``None`` the graphics
+	/// queue is used for transfers.
+	pub fn from_existing(
+		device: Arc<Device>,
+		graphics_queue: Arc<device::Queue>,
+		transfer_queue: Option<Arc<device::Queue>>,
+		format: Format,
+		mut options: Options
+	) -> Arc<Self> {
+		let prefs = options.open_prefs();
+		
+		if let Some(scale) = prefs.as_ref().and_then(|prefs| prefs.get().scale) {
+			options.scale = scale;
+		}
+		
+		let initials = Initials::from_existing(device, graphics_queue, transfer_queue, &options);
+		Self::with_initials(initials, options, format, prefs)
+	}
+	
+	fn with_initials(initials: Initials, options: Options, target_format: Format, prefs: Option<PrefsStore>) -> Arc<Self> {
+		let clipboard = Clipboard::new(options.headless.is_none());
+		
+		unsafe {
 			let mut basalt_ret = Arc::new(Basalt {
 				device: initials.device,
 				graphics_queue: initials.graphics_queue,
 				transfer_queue: initials.transfer_queue,
 				surface: initials.surface,
 				swap_caps: initials.swap_caps,
-				do_every: RwLock::new(Vec::new()),
+				scheduler: Scheduler::new(),
 				mouse_capture: AtomicBool::new(false),
 				allow_mouse_cap: AtomicBool::new(true),
 				fps: AtomicUsize::new(0),
@@ -369,18 +1071,42 @@ impl Basalt {
 				limits: initials.limits.clone(),
 				atlas: ::std::mem::uninitialized(),
 				input: ::std::mem::uninitialized(),
+				clipboard,
 				wants_exit: AtomicBool::new(false),
 				force_resize: AtomicBool::new(false),
 				resize_requested: AtomicBool::new(false),
 				resize_to: Mutex::new(None),
+				window_cmds: Mutex::new(Vec::new()),
 				loop_thread: Mutex::new(None),
 				pdevi: initials.pdevi,
-				vsync: Mutex::new(true),
+				present_mode: Mutex::new(options.present_mode),
+				frame_limit: Mutex::new(options.frame_limit),
+				frame_times: Mutex::new(VecDeque::with_capacity(misc::frame::FRAME_TIMES_LEN)),
+				redraw: Mutex::new(1),
+				redraw_cond: Condvar::new(),
 				wait_on_futures: Mutex::new(Vec::new()),
 				window_size: Mutex::new(initials.window_size),
 				custom_scale: Mutex::new(options.scale),
 				options,
 				ignore_dpi_data: Mutex::new(None),
+				headless_frame: Mutex::new(None),
+				frame_captures: Mutex::new(Vec::new()),
+				target_format,
+				event_hooks: Mutex::new(BTreeMap::new()),
+				event_hook_id: AtomicUsize::new(0),
+				render_hooks: Mutex::new(BTreeMap::new()),
+				render_hook_id: AtomicUsize::new(0),
+				events_proxy: Mutex::new(initials.events_proxy),
+				windows: Mutex::new(Vec::new()),
+				window_requests: SegQueue::new(),
+				closed_surfaces: SegQueue::new(),
+				threads: Mutex::new(initials.event_thread.into_iter().collect()),
+				on_exit: Mutex::new(Vec::new()),
+				shut_down: AtomicBool::new(false),
+				debug_reporter: initials.debug_reporter,
+				warnings: logging::Warnings::new(),
+				prefs,
+				fullscreen: AtomicBool::new(false),
 			});
 			
 			let atlas_ptr = &mut Arc::get_mut(&mut basalt_ret).unwrap().atlas as *mut _;
@@ -391,29 +1117,56 @@ impl Basalt {
 			::std::ptr::write(input_ptr, Input::new(basalt_ret.clone()));
 			
 			if !basalt_ret.options.ignore_dpi {
-				basalt_ret.interface_ref().set_scale(basalt_ret.surface.window().get_hidpi_factor() as f32 * basalt_ret.options.scale);
+				basalt_ret.interface_ref().set_scale(basalt_ret.hidpi_factor() as f32 * basalt_ret.options.scale);
 			} else if basalt_ret.options.scale != 1.0 {
 				basalt_ret.interface_ref().set_scale(basalt_ret.options.scale);
 			}
 			
-			*initials.event_mk.lock() = Some(basalt_ret.clone());
-			initials.event_mk_br.wait();
+			if basalt_ret.surface.is_some() {
+				*initials.event_mk.lock() = Some(basalt_ret.clone());
+				initials.event_mk_br.wait();
+			}
+			
+			if let Some(prefs) = basalt_ret.prefs.as_ref().map(|prefs| prefs.get()) {
+				if let Some(msaa) = prefs.msaa {
+					if let Err(e) = basalt_ret.interface_ref().set_msaa(msaa) {
+						basalt_ret.warn(logging::INIT, format!("Preferences contain an invalid msaa: {}", e));
+					}
+				}
+				
+				if basalt_ret.surface.is_some() {
+					if let Some(position) = prefs.window_position {
+						basalt_ret.set_position(position[0], position[1]);
+					}
+					
+					if prefs.fullscreen == Some(true) {
+						basalt_ret.fullscreen(true);
+					}
+				}
+			}
+			
+			let scale_help = match &basalt_ret.options.scale_hotkeys {
+				Some(hotkeys) => format!(
+					"\r\n             {}: Decreases ui scale\
+					\r\n             {}: Increases ui scale",
+					ScaleHotkeys::describe(&hotkeys.decrease),
+					ScaleHotkeys::describe(&hotkeys.increase)
+				), None => String::new()
+			};
 			
 			basalt_ret.input_ref().add_hook(input::InputHook::Press {
 				global: false,
 				keys: vec![input::Qwery::F1],
 				mouse_buttons: Vec::new()
 			}, Arc::new(move |_| {
-				println!("\
+				info!(target: logging::INPUT, "\
 			    -------------------------------------\r\n\
 	             F1: Prints keys used by basalt\r\n\
 	             F2: Prints fps while held\r\n\
 	             F7: Decreases msaa level\r\n\
 	             F8: Increases msaa level\r\n\
-	             F10: Toggles vsync\r\n\
-	             LCtrl + Dash: Decreases ui scale\r\n\
-	             LCtrl + Equal: Increaes ui scale\r\n\
-			    -------------------------------------");
+	             F10: Toggles vsync{}\r\n\
+			    -------------------------------------", scale_help);
 				input::InputHookRes::Success
 			}));
 			
@@ -426,7 +1179,7 @@ impl Basalt {
 				interval: Duration::from_millis(100),
 				accel: 0.0,
 			}, Arc::new(move |_| {
-				println!("FPS: {}", basalt.fps());
+				info!(target: logging::RENDER, "FPS: {}", basalt.fps());
 				input::InputHookRes::Success
 			}));
 			
@@ -437,7 +1190,7 @@ impl Basalt {
 				mouse_buttons: Vec::new()
 			}, Arc::new(move |_| {
 				basalt.interface_ref().decrease_msaa();
-				println!("MSAA set to {}X", basalt.interface_ref().msaa());
+				info!(target: logging::RENDER, "MSAA set to {}X", basalt.interface_ref().msaa());
 				input::InputHookRes::Success
 			}));
 			
@@ -448,7 +1201,7 @@ impl Basalt {
 				mouse_buttons: Vec::new()
 			}, Arc::new(move |_| {
 				basalt.interface_ref().increase_msaa();
-				println!("MSAA set to {}X", basalt.interface_ref().msaa());
+				info!(target: logging::RENDER, "MSAA set to {}X", basalt.interface_ref().msaa());
 				input::InputHookRes::Success
 			}));
 			
@@ -458,74 +1211,191 @@ impl Basalt {
 				keys: vec![input::Qwery::F10],
 				mouse_buttons: Vec::new()
 			}, Arc::new(move |_| {
-				let mut vsync = basalt.vsync.lock();
-				*vsync = !*vsync;
-				basalt.force_resize.store(true, atomic::Ordering::Relaxed);
+				let vsync = !basalt.present_mode().is_vsync();
 				
-				if *vsync {
-					println!("VSync Enabled!");
+				basalt.set_present_mode(if vsync {
+					PresentMode::Relaxed
 				} else {
-					println!("VSync Disabled!");
-				}
-				
-				input::InputHookRes::Success
-			}));
-			
-			let basalt = basalt_ret.clone();
-			basalt_ret.input_ref().add_hook(input::InputHook::Press {
-				global: false,
-				keys: vec![input::Qwery::LCtrl, input::Qwery::Dash],
-				mouse_buttons: Vec::new()
-			}, Arc::new(move |_| {
-				basalt.add_scale(-0.05);
+					PresentMode::Mailbox
+				});
 				
-				if basalt.options.ignore_dpi {
-					println!("Current Scale: {:.1} %", basalt.current_scale() * 100.0);
+				if vsync {
+					info!(target: logging::RENDER, "VSync Enabled!");
 				} else {
-					println!("Current Scale: {:.1} %", basalt.current_scale_with_dpi() * 100.0);
+					info!(target: logging::RENDER, "VSync Disabled!");
 				}
 				
 				input::InputHookRes::Success
 			}));
 			
-			let basalt = basalt_ret.clone();
-			basalt_ret.input_ref().add_hook(input::InputHook::Press {
-				global: false,
-				keys: vec![input::Qwery::LCtrl, input::Qwery::Equal],
-				mouse_buttons: Vec::new()
-			}, Arc::new(move |_| {
-				basalt.add_scale(0.05);
-				
-				if basalt.options.ignore_dpi {
-					println!("Current Scale: {:.1} %", basalt.current_scale() * 100.0);
-				} else {
-					println!("Current Scale: {:.1} %", basalt.current_scale_with_dpi() * 100.0);
+			if let Some(hotkeys) = basalt_ret.options.scale_hotkeys.clone() {
+				for (keys, amt) in vec![(hotkeys.decrease, -0.05), (hotkeys.increase, 0.05)] {
+					let basalt = basalt_ret.clone();
+					basalt_ret.input_ref().add_hook(input::InputHook::Press {
+						global: false,
+						keys,
+						mouse_buttons: Vec::new()
+					}, Arc::new(move |_| {
+						basalt.add_scale(amt);
+						
+						if basalt.options.ignore_dpi {
+							info!(target: logging::INTERFACE, "Current Scale: {:.1} %", basalt.current_scale() * 100.0);
+						} else {
+							info!(target: logging::INTERFACE, "Current Scale: {:.1} %", basalt.current_scale_with_dpi() * 100.0);
+						}
+						
+						input::InputHookRes::Success
+					}));
 				}
-				
-				input::InputHookRes::Success
-			}));
+			}
 			
-			Ok(basalt_ret)
+			basalt_ret
+		}
+	}
+	
+	/// Call the provided function whenever a ``BasaltEvent`` occurs. The function is called
+	/// from whichever thread the event occured on.
+	pub fn on_event(&self, func: Arc<Fn(&BasaltEvent) + Send + Sync>) -> BasaltEventHookID {
+		let id = self.event_hook_id.fetch_add(1, atomic::Ordering::SeqCst) as BasaltEventHookID;
+		self.event_hooks.lock().insert(id, func);
+		id
+	}
+	
+	pub fn remove_event_hook(&self, id: BasaltEventHookID) {
+		self.event_hooks.lock().remove(&id);
+	}
+	
+	/// Open an additional window. The window shares the device, atlas and text cache, but
+	/// has its own swapchain and ``Interface``. Input to the window is sent only to bin hooks
+	/// of its interface, ``Input`` hooks only receive input from the main window. This can't
+	/// be called from the event loop thread and fails when basalt has no window.
+	pub fn create_window(&self, options: WindowOptions) -> Result<Arc<BasaltWindow>, BasaltError> {
+		let request = WindowRequest::new(options);
+		let (result, condvar) = request.handles();
+		
+		match self.events_proxy.lock().as_ref() {
+			Some(proxy) => {
+				self.window_requests.push(request);
+				
+				if proxy.wakeup().is_err() {
+					return Err(InitError::NoEventLoop.into());
+				}
+			},
+			None => return Err(InitError::NoEventLoop.into())
+		}
+		
+		WindowRequest::wait(result, condvar)
+	}
+	
+	/// Windows created with ``create_window()`` that haven't been closed.
+	pub fn windows(&self) -> Vec<Arc<BasaltWindow>> {
+		self.windows.lock().clone()
+	}
+	
+	pub fn window_by_id(&self, id: winit::WindowId) -> Option<Arc<BasaltWindow>> {
+		self.windows.lock().iter().find(|w| w.id() == id).cloned()
+	}
+	
+	pub(crate) fn remove_window(&self, id: winit::WindowId) {
+		self.windows.lock().retain(|w| w.id() != id);
+	}
+	
+	/// Windows have to be destroyed on the event loop thread, so the surfaces of closed
+	/// windows are handed to it.
+	pub(crate) fn destroy_surface(&self, surface: Arc<Surface<Window>>) {
+		self.closed_surfaces.push(surface);
+		
+		if let Some(proxy) = self.events_proxy.lock().as_ref() {
+			let _ = proxy.wakeup();
+		}
+	}
+	
+	/// Drops the surfaces of closed windows. Called from the event loop thread.
+	pub(crate) fn drop_closed_surfaces(&self) {
+		while let Ok(surface) = self.closed_surfaces.pop() {
+			drop(surface);
+		}
+	}
+	
+	/// Creates the windows that have been requested. Called from the event loop thread.
+	pub(crate) fn create_requested_windows(self: &Arc<Self>, events_loop: &winit::EventsLoop) {
+		while let Ok(request) = self.window_requests.pop() {
+			request.complete(self, events_loop);
+		}
+	}
+	
+	/// The main interface along with the interfaces of every window.
+	pub(crate) fn interfaces(&self) -> Vec<Arc<Interface>> {
+		let mut interfaces = vec![self.interface.clone()];
+		interfaces.extend(self.windows.lock().iter().map(|w| w.interface()));
+		interfaces
+	}
+	
+	/// Call the provided function every frame rendered by the app loop so that content
+	/// can be drawn beneath or above the interface. Hooks are only called when basalt has
+	/// a window.
+	pub fn add_render_hook(&self, stage: RenderStage, func: RenderHookFn) -> RenderHookID {
+		let id = self.next_render_hook_id();
+		self.render_hooks.lock().insert(id, (stage, func));
+		self.request_redraw();
+		id
+	}
+	
+	pub fn remove_render_hook(&self, id: RenderHookID) {
+		self.render_hooks.lock().remove(&id);
+		self.request_redraw();
+	}
+	
+	/// Render hook ids are shared with windows so that they are never reused.
+	pub(crate) fn next_render_hook_id(&self) -> RenderHookID {
+		self.render_hook_id.fetch_add(1, atomic::Ordering::SeqCst) as RenderHookID
+	}
+	
+	/// Request a new frame to be rendered. This is only needed when using on demand
+	/// rendering and something not managed by basalt has changed.
+	pub fn request_redraw(&self) {
+		*self.redraw.lock() += 1;
+		self.redraw_cond.notify_all();
+	}
+	
+	/// Blocks until a redraw is requested when on demand rendering is enabled. ``seen`` is
+	/// the last request the calling render loop has handled, so every loop gets woken up.
+	pub(crate) fn wait_for_redraw(&self, seen: &mut u64) {
+		if !self.options.on_demand {
+			return;
+		}
+		
+		let mut redraw = self.redraw.lock();
+		
+		while *redraw == *seen {
+			self.redraw_cond.wait(&mut redraw);
 		}
+		
+		*seen = *redraw;
 	}
 	
 	pub fn send_event(&self, event: BasaltEvent) {
-		match event {
+		match &event {
 			BasaltEvent::WindowResized => {
 				if self.options.ignore_dpi {
 					if let Some((count, last, w, h)) = &mut *self.ignore_dpi_data.lock() {
-						println!("{} {} {} {:?}", count, w, h, *self.window_size.lock());
+						trace!(target: logging::RENDER, "ignore_dpi resize: {} {} {} {:?}", count, w, h, *self.window_size.lock());
+						
+						let surface = match self.surface.as_ref() {
+							Some(some) => some,
+							None => return
+						};
 						
 						if *count == 1 {
-							self.surface.window().set_inner_size(winit::dpi::PhysicalSize::new(
+							surface.window().set_inner_size(winit::dpi::PhysicalSize::new(
 								*w as f64,
 								*h as f64
-							).to_logical(self.surface.window().get_hidpi_factor()));
+							).to_logical(surface.window().get_hidpi_factor()));
 						} else if *count == 3 && last.elapsed() < Duration::from_millis(1000) { // TODO: Only if right click released
-							self.surface.window().set_inner_size(winit::dpi::PhysicalSize::new(
+							surface.window().set_inner_size(winit::dpi::PhysicalSize::new(
 								*w as f64,
 								*h as f64
-							).to_logical(self.surface.window().get_hidpi_factor()));
+							).to_logical(surface.window().get_hidpi_factor()));
 						} else {
 							self.force_resize.store(true, atomic::Ordering::Relaxed);
 						}
@@ -544,9 +1414,19 @@ impl Basalt {
 					let ws = self.window_size.lock();
 					*self.ignore_dpi_data.lock() = Some((0, Instant::now(), ws[0], ws[1]));
 				} else {
-					self.interface_ref().set_scale(dpi as f32 * *self.custom_scale.lock());
+					let scale = *dpi as f32 * *self.custom_scale.lock();
+					self.interface_ref().set_scale(scale);
 				}
-			}
+			},
+			
+			_ => ()
+		}
+		
+		self.request_redraw();
+		let hooks: Vec<_> = self.event_hooks.lock().values().cloned().collect();
+		
+		for hook in hooks {
+			hook(&event);
 		}
 	}
 	
@@ -563,42 +1443,166 @@ impl Basalt {
 	}
 	
 	pub fn current_scale_with_dpi(&self) -> f32 {
-		*self.custom_scale.lock() * self.surface.window().get_hidpi_factor() as f32
+		*self.custom_scale.lock() * self.hidpi_factor() as f32
+	}
+	
+	/// The hidpi factor of the window. Always ``1.0`` when headless.
+	pub fn hidpi_factor(&self) -> f64 {
+		match self.surface.as_ref() {
+			Some(surface) => surface.window().get_hidpi_factor(),
+			None => 1.0
+		}
 	}
 	
 	pub fn set_scale(&self, to: f32) {
-		let mut custom_scale = self.custom_scale.lock();
-		*custom_scale = to;
+		let custom_scale = {
+			let mut custom_scale = self.custom_scale.lock();
+			*custom_scale = to;
+			*custom_scale
+		};
 		
 		if self.options.ignore_dpi {
-			self.interface_ref().set_scale(*custom_scale);
+			self.interface_ref().set_scale(custom_scale);
 		} else {
-			self.interface_ref().set_scale(*custom_scale
-				* self.surface.window().get_hidpi_factor() as f32);
+			self.interface_ref().set_scale(custom_scale
+				* self.hidpi_factor() as f32);
 		}
 	}
 	
 	pub fn add_scale(&self, amt: f32) {
-		let mut custom_scale = self.custom_scale.lock();
-		*custom_scale += amt;
+		let custom_scale = {
+			let mut custom_scale = self.custom_scale.lock();
+			*custom_scale += amt;
+			*custom_scale
+		};
 		
 		if self.options.ignore_dpi {
-			self.interface_ref().set_scale(*custom_scale);
+			self.interface_ref().set_scale(custom_scale);
 		} else {
-			self.interface_ref().set_scale(*custom_scale
-				* self.surface.window().get_hidpi_factor() as f32);
+			self.interface_ref().set_scale(custom_scale
+				* self.hidpi_factor() as f32);
 		}
 	}
 	
 	
-	/// This will only work if the basalt is handling the loop thread. This
-	/// is done via the method ``spawn_app_loop()``
-	pub fn wait_for_exit(&self) -> Result<(), String> {
-		match self.loop_thread.lock().take() {
-			Some(handle) => match handle.join() {
-				Ok(ok) => ok,
-				Err(_) => Err(format!("Failed to join loop thread."))
+	/// Waits for the app loop to exit if it was started with ``spawn_app_loop()``, then
+	/// shuts basalt down if an exit was requested or the app loop has stopped. Shutting down
+	/// closes every window, joins all of basalt's threads, clears hooks and scheduled tasks,
+	/// and then calls the ``on_exit()`` callbacks. Once the application drops its references
+	/// to basalt and its bins, the device and all gpu resources are released, so another
+	/// basalt can be created afterwards in the same process.
+	pub fn wait_for_exit(&self) -> Result<(), BasaltError> {
+		let result = match self.loop_thread.lock().take() {
+			Some(handle) => {
+				let result = match handle.join() {
+					Ok(ok) => ok,
+					Err(_) => Err(RenderError::LoopThreadPanicked.into())
+				};
+				
+				self.exit();
+				result
 			}, None => Ok(())
+		};
+		
+		if self.wants_exit() {
+			self.shutdown();
+		}
+		
+		result
+	}
+	
+	/// Write the current scale, msaa, window size, position and fullscreen state to the
+	/// preferences file. This is done automatically when shutting down. Does nothing unless
+	/// enabled with ``Options::prefs()``.
+	pub fn save_prefs(&self) -> Result<(), BasaltError> {
+		let store = match self.prefs.as_ref() {
+			Some(some) => some,
+			None => return Ok(())
+		};
+		
+		let mut prefs = store.get();
+		prefs.scale = Some(self.current_scale());
+		prefs.msaa = Some(self.interface_ref().msaa());
+		
+		if self.surface.is_some() {
+			let fullscreen = self.is_fullscreen();
+			prefs.fullscreen = Some(fullscreen);
+			
+			// Keep the windowed size and position to return to.
+			if !fullscreen {
+				let size = *self.window_size.lock();
+				
+				prefs.window_size = Some(if self.options.ignore_dpi {
+					size
+				} else {
+					let hidpi_factor = self.hidpi_factor();
+					[
+						(size[0] as f64 / hidpi_factor).round() as u32,
+						(size[1] as f64 / hidpi_factor).round() as u32
+					]
+				});
+				
+				if let Some(position) = self.window_position() {
+					prefs.window_position = Some(position);
+				}
+			}
+		}
+		
+		store.save(prefs)
+	}
+	
+	/// The preferences file, if enabled with ``Options::prefs()``.
+	pub fn prefs_path(&self) -> Option<&Path> {
+		self.prefs.as_ref().map(|prefs| prefs.path())
+	}
+	
+	/// Call ``func`` once basalt has shut down. All of basalt's threads have exited by then.
+	pub fn on_exit(&self, func: Arc<Fn() + Send + Sync>) {
+		self.on_exit.lock().push(func);
+	}
+	
+	/// Keep track of a background thread so that it is joined when shutting down. The
+	/// thread must exit on its own once ``wants_exit()`` is true or basalt is dropped.
+	pub(crate) fn track_thread(&self, handle: JoinHandle<()>) {
+		self.threads.lock().push(handle);
+	}
+	
+	fn shutdown(&self) {
+		if self.shut_down.swap(true, atomic::Ordering::SeqCst) {
+			return;
+		}
+		
+		let windows = self.windows.lock().split_off(0);
+		
+		for window in windows {
+			window.close();
+			let _ = window.wait_for_close();
+		}
+		
+		let threads = self.threads.lock().split_off(0);
+		let current = thread::current().id();
+		
+		for handle in threads {
+			// When shutting down from a callback running on one of basalt's threads, that
+			// thread exits after the callback returns.
+			if handle.thread().id() != current {
+				let _ = handle.join();
+			}
+		}
+		
+		self.scheduler.shutdown();
+		
+		if let Err(e) = self.save_prefs() {
+			self.warn(logging::INIT, format!("Failed to save preferences: {}", e));
+		}
+		
+		self.event_hooks.lock().clear();
+		self.render_hooks.lock().clear();
+		self.interface.capture_renderer.lock().take();
+		let on_exit = self.on_exit.lock().split_off(0);
+		
+		for func in on_exit {
+			func();
 		}
 	}
 	
@@ -610,26 +1614,206 @@ impl Basalt {
 		}));
 	}
 	
+	/// Update the size the interface is laid out for. This is only needed when created with
+	/// ``from_existing()``, otherwise the app loop keeps it up to date.
+	pub fn set_window_size(&self, w: u32, h: u32) {
+		*self.window_size.lock() = [w, h];
+		self.send_event(BasaltEvent::WindowResized);
+	}
+	
 	/// only works with app loop
 	pub fn resize(&self, w: u32, h: u32) {
 		*self.resize_to.lock() = Some(ResizeTo::Dims(w, h));
 		self.resize_requested.store(true, atomic::Ordering::Relaxed);
+		self.request_redraw();
 	}
 	
 	/// only works with app loop
 	pub fn fullscreen(&self, fullscreen: bool) {
+		self.fullscreen.store(fullscreen, atomic::Ordering::SeqCst);
 		*self.resize_to.lock() = Some(ResizeTo::FullScreen(fullscreen));
 		self.resize_requested.store(true, atomic::Ordering::Relaxed);
+		self.request_redraw();
+	}
+	
+	/// True if fullscreen was last requested with ``fullscreen()`` or ``fullscreen_on()``.
+	pub fn is_fullscreen(&self) -> bool {
+		self.fullscreen.load(atomic::Ordering::SeqCst)
+	}
+	
+	fn window_cmd(&self, cmd: WindowCmd) {
+		if self.surface.is_some() {
+			self.window_cmds.lock().push(cmd);
+			self.request_redraw();
+		}
+	}
+	
+	/// Converts to a logical size unless ``ignore_dpi`` is set.
+	fn logical_size(&self, window: &Window, w: u32, h: u32) -> winit::dpi::LogicalSize {
+		if self.options.ignore_dpi {
+			winit::dpi::PhysicalSize::new(w as f64, h as f64).to_logical(window.get_hidpi_factor())
+		} else {
+			winit::dpi::LogicalSize::new(w as f64, h as f64)
+		}
+	}
+	
+	/// only works with app loop
+	pub fn set_title<T: AsRef<str>>(&self, title: T) {
+		self.window_cmd(WindowCmd::Title(String::from(title.as_ref())));
+	}
+	
+	/// only works with app loop
+	///
+	/// There is no ``set_minimized()`` counterpart since winit 0.18 has no way to minimize a window.
+	pub fn set_maximized(&self, maximized: bool) {
+		self.window_cmd(WindowCmd::Maximized(maximized));
+	}
+	
+	/// Move the window's top left corner to the position. The position is physical if
+	/// ``ignore_dpi`` is set and logical otherwise. only works with app loop
+	pub fn set_position(&self, x: i32, y: i32) {
+		self.window_cmd(WindowCmd::Position(x, y));
+	}
+	
+	/// The position of the window's top left corner in the same units as ``set_position()``.
+	pub fn window_position(&self) -> Option<[i32; 2]> {
+		let window = self.surface.as_ref()?.window();
+		let position = window.get_position()?;
+		
+		let (x, y) = if self.options.ignore_dpi {
+			let position = position.to_physical(window.get_hidpi_factor());
+			(position.x, position.y)
+		} else {
+			(position.x, position.y)
+		};
+		
+		Some([x as i32, y as i32])
+	}
+	
+	/// only works with app loop
+	pub fn set_min_size(&self, size: Option<[u32; 2]>) {
+		self.window_cmd(WindowCmd::MinSize(size));
+	}
+	
+	/// only works with app loop
+	pub fn set_max_size(&self, size: Option<[u32; 2]>) {
+		self.window_cmd(WindowCmd::MaxSize(size));
+	}
+	
+	/// only works with app loop
+	pub fn set_decorations(&self, decorations: bool) {
+		self.window_cmd(WindowCmd::Decorations(decorations));
 	}
 	
 	/// only works with app loop
+	pub fn set_always_on_top(&self, always_on_top: bool) {
+		self.window_cmd(WindowCmd::AlwaysOnTop(always_on_top));
+	}
+	
+	/// Set the window icon from rgba data. only works with app loop
+	pub fn set_icon(&self, rgba: Vec<u8>, width: u32, height: u32) -> Result<(), BasaltError> {
+		let icon = winit::Icon::from_rgba(rgba, width, height).map_err(|e| WindowError::BadIcon(e))?;
+		self.window_cmd(WindowCmd::Icon(Some(icon)));
+		Ok(())
+	}
+	
+	/// only works with app loop
+	pub fn clear_icon(&self) {
+		self.window_cmd(WindowCmd::Icon(None));
+	}
+	
+	/// Borderless fullscreen on the monitor at the index given by ``monitors()``. Use
+	/// ``fullscreen(false)`` to leave fullscreen. only works with app loop
+	pub fn fullscreen_on(&self, monitor: usize) -> Result<(), BasaltError> {
+		let window = match self.surface.as_ref() {
+			Some(some) => some.window(),
+			None => return Err(WindowError::NoWindow.into())
+		};
+		
+		match window.get_available_monitors().nth(monitor) {
+			Some(some) => {
+				self.fullscreen.store(true, atomic::Ordering::SeqCst);
+				self.window_cmd(WindowCmd::FullScreenOn(some));
+				Ok(())
+			},
+			None => Err(WindowError::MonitorNotFound(monitor).into())
+		}
+	}
+	
+	/// Monitors available to the window. Empty when headless.
+	pub fn monitors(&self) -> Vec<MonitorInfo> {
+		let window = match self.surface.as_ref() {
+			Some(some) => some.window(),
+			None => return Vec::new()
+		};
+		
+		window.get_available_monitors().enumerate().map(|(index, monitor)| {
+			let position = monitor.get_position();
+			let size = monitor.get_dimensions();
+			
+			MonitorInfo {
+				index,
+				name: monitor.get_name(),
+				position: [position.x as i32, position.y as i32],
+				size: [size.width as u32, size.height as u32],
+				hidpi_factor: monitor.get_hidpi_factor(),
+			}
+		}).collect()
+	}
+	
+	/// Index into ``monitors()`` of the monitor the window is currently on.
+	pub fn current_monitor(&self) -> Option<usize> {
+		let current = self.surface.as_ref()?.window().get_current_monitor();
+		let position = current.get_position();
+		let name = current.get_name();
+		
+		self.monitors().into_iter().find(|m| {
+			m.name == name && m.position == [position.x as i32, position.y as i32]
+		}).map(|m| m.index)
+	}
+	
+	fn apply_window_cmd(&self, window: &Window, cmd: WindowCmd) {
+		match cmd {
+			WindowCmd::Title(title) => window.set_title(&title),
+			WindowCmd::Maximized(maximized) => window.set_maximized(maximized),
+			WindowCmd::Position(x, y) => window.set_position(if self.options.ignore_dpi {
+				winit::dpi::PhysicalPosition::new(x as f64, y as f64).to_logical(window.get_hidpi_factor())
+			} else {
+				winit::dpi::LogicalPosition::new(x as f64, y as f64)
+			}),
+			WindowCmd::MinSize(size) => window.set_min_dimensions(size.map(|[w, h]| self.logical_size(window, w, h))),
+			WindowCmd::MaxSize(size) => window.set_max_dimensions(size.map(|[w, h]| self.logical_size(window, w, h))),
+			WindowCmd::Decorations(decorations) => window.set_decorations(decorations),
+			WindowCmd::AlwaysOnTop(always_on_top) => window.set_always_on_top(always_on_top),
+			WindowCmd::Icon(icon) => window.set_window_icon(icon),
+			WindowCmd::FullScreenOn(monitor) => window.set_fullscreen(Some(monitor)),
+		}
+	}
+	
+	/// Request basalt to exit. The app loop and background threads are woken up and stop on
+	/// their own, use ``wait_for_exit()`` to wait for them and finish shutting down.
 	pub fn exit(&self) {
-		self.wants_exit.store(true, atomic::Ordering::Relaxed);
+		self.wants_exit.store(true, atomic::Ordering::SeqCst);
+		self.request_redraw();
+		self.atlas.wake();
+		
+		for interface in self.interfaces() {
+			interface.odb.unpark();
+		}
+		
+		if let Some(proxy) = self.events_proxy.lock().as_ref() {
+			let _ = proxy.wakeup();
+		}
 	}
 	
 	/// only works with app loop
-	pub fn do_every(&self, func: Arc<Fn() + Send + Sync>) {
-		self.do_every.write().push(func);
+	///
+	/// Shorthand for ``scheduler_ref().on_frame()`` for functions that run every frame.
+	pub fn do_every(&self, func: Arc<Fn() + Send + Sync>) -> TaskID {
+		self.scheduler.on_frame(Arc::new(move |_| {
+			func();
+			TaskRes::Continue
+		}))
 	}
 	
 	/// only works with app loop
@@ -637,15 +1821,75 @@ impl Basalt {
 		self.fps.load(atomic::Ordering::Relaxed)
 	}
 	
+	/// Warnings logged by this basalt, oldest first. Only the most recent are kept.
+	pub fn recent_warnings(&self) -> Vec<logging::Warning> {
+		self.warnings.recent()
+	}
+	
+	pub fn clear_warnings(&self) {
+		self.warnings.clear();
+	}
+	
+	/// Log a warning under ``target`` and keep it for ``recent_warnings()``.
+	pub(crate) fn warn(&self, target: &'static str, message: String) {
+		self.warnings.warn(target, message);
+	}
+	
+	/// Frame time statistics over the most recent frames.
+	pub fn frame_stats(&self) -> misc::FrameStats {
+		misc::FrameStats::from_times(&*self.frame_times.lock())
+	}
+	
+	fn record_frame_time(&self, last_frame: &mut Instant) {
+		let elapsed = last_frame.elapsed();
+		*last_frame = Instant::now();
+		let mut frame_times = self.frame_times.lock();
+		
+		if frame_times.len() >= misc::frame::FRAME_TIMES_LEN {
+			frame_times.pop_front();
+		}
+		
+		frame_times.push_back((elapsed.as_secs() * 1000) as f32 + (elapsed.subsec_nanos() as f32 / 1000000.0));
+	}
+	
+	pub fn present_mode(&self) -> PresentMode {
+		*self.present_mode.lock()
+	}
+	
+	/// Change the present mode. This will cause the swapchain to be recreated.
+	pub fn set_present_mode(&self, mode: PresentMode) {
+		*self.present_mode.lock() = mode;
+		self.force_resize.store(true, atomic::Ordering::Relaxed);
+		self.request_redraw();
+	}
+	
+	pub fn frame_limit(&self) -> Option<u32> {
+		*self.frame_limit.lock()
+	}
+	
+	/// Limit the amount of frames rendered per second. ``None`` removes the limit.
+	pub fn set_frame_limit(&self, fps: Option<u32>) {
+		*self.frame_limit.lock() = fps;
+	}
+	
 	/// only works with app loop
 	pub fn wait_on_gpu_future(&self, future: Box<GpuFuture + Send + Sync>, barrier: Arc<Barrier>) {
 		self.wait_on_futures.lock().push((future, barrier));
+		self.request_redraw();
 	}
 	
 	pub fn interface(&self) -> Arc<Interface> {
 		self.interface.clone()
 	} pub fn interface_ref(&self) -> &Arc<Interface> {
 		&self.interface
+	} pub fn clipboard(&self) -> Arc<Clipboard> {
+		self.clipboard.clone()
+	} pub fn clipboard_ref(&self) -> &Arc<Clipboard> {
+		&self.clipboard
+	} pub fn scheduler(&self) -> Arc<Scheduler> {
+		self.scheduler.clone()
+	} pub fn scheduler_ref(&self) -> &Arc<Scheduler> {
+		&self.scheduler
 	} pub fn atlas(&self) -> Arc<Atlas> {
 		self.atlas.clone()
 	} pub fn mouse_captured(&self) -> bool {
@@ -670,101 +1914,186 @@ impl Basalt {
 		&self.graphics_queue
 	} pub fn physical_device_index(&self) -> usize {
 		self.pdevi
-	} pub fn surface(&self) -> Arc<Surface<Window>> {
+	} pub fn surface(&self) -> Option<Arc<Surface<Window>>> {
 		self.surface.clone()
-	} pub fn surface_ref(&self) -> &Arc<Surface<Window>> {
-		&self.surface
-	} pub fn swap_caps(&self) -> &swapchain::Capabilities {
-		&self.swap_caps
+	} pub fn surface_ref(&self) -> Option<&Arc<Surface<Window>>> {
+		self.surface.as_ref()
+	} pub fn swap_caps(&self) -> Option<&swapchain::Capabilities> {
+		self.swap_caps.as_ref()
+	} pub fn is_headless(&self) -> bool {
+		self.surface.is_none()
+	} pub fn window_size(&self) -> [u32; 2] {
+		*self.window_size.lock()
+	} pub fn target_format(&self) -> Format {
+		self.target_format
 	} pub fn wants_exit(&self) -> bool {
-		self.wants_exit.load(atomic::Ordering::Relaxed)
+		self.wants_exit.load(atomic::Ordering::SeqCst)
 	}
 	
 	pub fn mouse_capture(&self, mut to: bool) {
 		if !self.mouse_cap_allowed() {
 			to = false;
 		} self.mouse_capture.store(to, atomic::Ordering::Relaxed);
+		self.request_redraw();
 	}
 	
-	pub fn app_loop(self: &Arc<Self>) -> Result<(), String> {
-		let mut win_size_x;
-		let mut win_size_y;
-		let mut frames = 0_usize;
-		let mut last_out = Instant::now();
-		let mut window_grab_cursor = false;
-		let mut swapchain_ = None;
-		let mut resized = false;
-		
-		let preferred_swap_formats = vec![
-			vulkano::format::Format::R8G8B8A8Srgb,
-			vulkano::format::Format::B8G8R8A8Srgb,
-		];
+	/// Capture the next frame rendered by the app loop. This blocks until the frame has
+	/// been rendered, so the app loop must be running on another thread.
+	pub fn capture_frame(&self) -> Result<image::RgbaImage, BasaltError> {
+		let capture = FrameCapture::new();
+		self.frame_captures.lock().push(capture.clone());
+		self.request_redraw();
+		capture.wait()
+	}
+	
+	/// Same as ``capture_frame()`` but saves the frame as a png.
+	pub fn capture_frame_png<P: AsRef<Path>>(&self, path: P) -> Result<(), BasaltError> {
+		let path = path.as_ref();
 		
-		let mut swapchain_format_ = None;
+		self.capture_frame()?.save(path).map_err(|e| {
+			RenderError::SaveImage(path.to_path_buf(), e).into()
+		})
+	}
+	
+	/// The most recent frame rendered while headless as srgb RGBA bytes. The dimensions
+	/// of the frame are that of ``window_size()``.
+	pub fn headless_frame(&self) -> Option<Vec<u8>> {
+		self.headless_frame.lock().clone()
+	}
+	
+	fn headless_loop(self: &Arc<Self>) -> Result<(), BasaltError> {
+		let mut itf_renderer = interface::render::ItfRenderer::new(self.clone());
+		let no_swap_imgs: Vec<Arc<SwapchainImage<()>>> = Vec::new();
+		let mut resized = true;
+		let mut frame_pacer = misc::frame::FramePacer::new();
+		let mut last_frame = Instant::now();
+		let mut redraw_seen = 0;
+		let mut readback = None;
 		
-		for a in &preferred_swap_formats {
-			for &(ref b, _) in &self.swap_caps.supported_formats {
-				if a == b {
-					swapchain_format_ = Some(*a);
-					break;
+		loop {
+			if !resized {
+				self.wait_for_redraw(&mut redraw_seen);
+				// Time spent idle waiting for a redraw isn't part of the frame.
+				last_frame = Instant::now();
+			}
+			
+			if self.resize_requested.swap(false, atomic::Ordering::Relaxed) {
+				if let Some(ResizeTo::Dims(w, h)) = self.resize_to.lock().take() {
+					if w == 0 || h == 0 {
+						self.warn(logging::RENDER, format!("Ignoring resize to {}x{}, headless frames can't be empty.", w, h));
+					} else {
+						*self.window_size.lock() = [w, h];
+						resized = true;
+					}
 				}
-			} if swapchain_format_.is_some() {
-				break;
 			}
+			
+			self.scheduler.run_frame();
+			
+			if self.force_resize.swap(false, atomic::Ordering::Relaxed) {
+				resized = true;
+			}
+			
+			let [w, h] = *self.window_size.lock();
+			let cmd_buf = AutoCommandBufferBuilder::primary_one_time_submit(self.device.clone(), self.graphics_queue.family())
+				.map_err(|e| RenderError::CreateCommandBuffer(e))?;
+			let (cmd_buf, _) = itf_renderer.draw(cmd_buf, [w, h], resized, &no_swap_imgs, false, 0);
+			
+			let target = match itf_renderer.target_image() {
+				Some(some) => some,
+				None => return Err(RenderError::NoTarget.into())
+			};
+			
+			// Only recreated when the size changes, the buffer is otherwise reused every frame.
+			if readback.as_ref().map(|r: &Readback| r.dimensions() != target.dimensions().width_height()).unwrap_or(true) {
+				readback = Some(Readback::new(self, &target)?);
+			}
+			
+			let readback_ref = readback.as_ref().unwrap();
+			let cmd_buf = readback_ref.record(cmd_buf, target)?.build().map_err(|e| RenderError::BuildCommandBuffer(e))?;
+			
+			cmd_buf.execute(self.graphics_queue.clone()).map_err(|e| RenderError::Execute(e))?
+				.then_signal_fence_and_flush().map_err(|e| RenderError::Flush(e))?
+				.wait(None).map_err(|e| RenderError::Flush(e))?;
+			
+			let frame = readback_ref.read()?;
+			
+			for capture in self.frame_captures.lock().split_off(0) {
+				capture.complete(Ok(frame.clone()));
+			}
+			
+			*self.headless_frame.lock() = Some(frame.into_raw());
+			frame_pacer.wait(self.frame_limit());
+			self.record_frame_time(&mut last_frame);
+			resized = false;
+			if self.wants_exit.load(atomic::Ordering::Relaxed) { break }
 		}
 		
-		let swapchain_format = match swapchain_format_ {
-			Some(some) => some,
-			None => return Err(format!("Failed to find capatible format for swapchain. Avaible formats: {:?}", self.swap_caps.supported_formats))
+		Ok(())
+	}
+	
+	/// Renders the interface to the window. When headless the interface is instead rendered
+	/// into an offscreen image that can be retrieved with ``headless_frame()``.
+	pub fn app_loop(self: &Arc<Self>) -> Result<(), BasaltError> {
+		let (surface, swap_caps) = match (self.surface.as_ref(), self.swap_caps.as_ref()) {
+			(Some(surface), Some(swap_caps)) => (surface, swap_caps),
+			_ => return self.headless_loop()
 		};
 		
+		let mut win_size_x;
+		let mut win_size_y;
+		let mut frames = 0_usize;
+		let mut last_out = Instant::now();
+		let mut window_grab_cursor = false;
+		let mut swapchain_ = None;
+		let mut frame_pacer = misc::frame::FramePacer::new();
+		let mut last_frame = Instant::now();
+		let mut redraw_seen = 0;
+		let mut resized = false;
+		
+		let swapchain_format = swapchain_format(swap_caps)?;
+		let composite_alpha = composite_alpha(swap_caps, self.options.transparent)?;
 		let mut itf_renderer = interface::render::ItfRenderer::new(self.clone());
+		let mut itf_compositor = interface::render::ItfCompositor::new(self.clone());
 		
 		'resize: loop {
-			let [x, y] = self.surface.capabilities(PhysicalDevice::from_index(
-				self.surface.instance(), self.pdevi).unwrap()).unwrap().current_extent.unwrap();
+			let [x, y] = surface_extent(surface, PhysicalDevice::from_index(surface.instance(), self.pdevi).unwrap())?;
 			win_size_x = x;
 			win_size_y = y;
 			*self.window_size.lock() = [x, y];
 			
-			let present_mode = if *self.vsync.lock() {
-				if self.swap_caps.present_modes.relaxed {
-					swapchain::PresentMode::Relaxed
-				} else {
-					swapchain::PresentMode::Fifo
-				}
-			} else {
-				if self.swap_caps.present_modes.mailbox {
-					swapchain::PresentMode::Mailbox
-				} else if self.swap_caps.present_modes.immediate {
-					swapchain::PresentMode::Immediate
-				} else {
-					swapchain::PresentMode::Fifo
-				}
-			};
+			let present_mode = self.present_mode().select(&swap_caps.present_modes);
 			
 			let old_swapchain = swapchain_.as_ref().map(|v: &(Arc<Swapchain<_>>, _)| v.0.clone());
 					
 			swapchain_ = Some(match Swapchain::new(
-				self.device.clone(), self.surface.clone(),
-				self.swap_caps.min_image_count, swapchain_format,
-				[x, y], 1, self.swap_caps.supported_usage_flags,
+				self.device.clone(), surface.clone(),
+				swap_caps.min_image_count, swapchain_format,
+				[x, y], 1, swap_caps.supported_usage_flags,
 				&self.graphics_queue, swapchain::SurfaceTransform::Identity,
-				swapchain::CompositeAlpha::Opaque, present_mode,
+				composite_alpha, present_mode,
 				true, old_swapchain.as_ref()
 			) {
 				Ok(ok) => ok,
 				Err(e) => {
-					if SHOW_SWAPCHAIN_WARNINGS { println!("swapchain recreation error: {:?}", e); }
+					debug!(target: logging::RENDER, "swapchain recreation error: {:?}", e);
 					continue;
 				}
 			});
 			
+			self.send_event(BasaltEvent::SwapchainRecreated);
+			
 			let (swapchain, images) = (&swapchain_.as_ref().unwrap().0, &swapchain_.as_ref().unwrap().1);
 			let mut previous_frame = Box::new(vulkano::sync::now(self.device.clone())) as Box<GpuFuture>;
 			let mut fps_avg = VecDeque::new();
 			
 			loop {
+				if !resized {
+					self.wait_for_redraw(&mut redraw_seen);
+					// Time spent idle waiting for a redraw isn't part of the frame.
+					last_frame = Instant::now();
+				}
+				
 				if self.resize_requested.load(atomic::Ordering::Relaxed) {
 					self.resize_requested.store(true, atomic::Ordering::Relaxed);
 					
@@ -772,22 +2101,12 @@ impl Basalt {
 						match resize_to {
 							ResizeTo::FullScreen(f) => match f {
 								true => {
-									self.surface.window().set_fullscreen(Some(self.surface.window().get_current_monitor()));
+									surface.window().set_fullscreen(Some(surface.window().get_current_monitor()));
 								}, false => {
-									self.surface.window().set_fullscreen(None);
+									surface.window().set_fullscreen(None);
 								}
 							}, ResizeTo::Dims(w, h) => {
-								self.surface.window().set_inner_size(if self.options.ignore_dpi {
-									winit::dpi::PhysicalSize::new(
-										w as f64,
-										h as f64
-									).to_logical(self.surface.window().get_hidpi_factor())
-								} else {
-									winit::dpi::LogicalSize::new(
-										w as f64,
-										h as f64
-									)
-								});
+								surface.window().set_inner_size(self.logical_size(surface.window(), w, h));
 							}
 						}
 						
@@ -796,6 +2115,10 @@ impl Basalt {
 					}
 				}
 				
+				for cmd in self.window_cmds.lock().split_off(0) {
+					self.apply_window_cmd(surface.window(), cmd);
+				}
+				
 				let duration = last_out.elapsed();
 				let millis = (duration.as_secs()*1000) as f32 + (duration.subsec_nanos() as f32/1000000.0);
 		
@@ -820,10 +2143,8 @@ impl Basalt {
 				}
 		
 				frames += 1;
-			
-				for func in &*self.do_every.read() {
-					func()
-				}
+				
+				self.scheduler.run_frame();
 				
 				if self.force_resize.swap(false, atomic::Ordering::Relaxed) {
 					resized = true;
@@ -833,14 +2154,21 @@ impl Basalt {
 				let (image_num, acquire_future) = match swapchain::acquire_next_image(swapchain.clone(), Some(::std::time::Duration::new(1, 0))) {
 					Ok(ok) => ok,
 					Err(e) => {
-						if SHOW_SWAPCHAIN_WARNINGS { println!("swapchain::acquire_next_image() Err: {:?}", e); }
+						debug!(target: logging::RENDER, "swapchain::acquire_next_image() Err: {:?}", e);
 						resized = true;
 						continue 'resize;
 					}
 				};
 				
 				let cmd_buf = AutoCommandBufferBuilder::primary_one_time_submit(self.device.clone(), self.graphics_queue.family()).unwrap();
-				let (cmd_buf, _) = itf_renderer.draw(cmd_buf, [win_size_x, win_size_y], resized, images, true, image_num);
+				let hooks = self.render_hooks.lock().values().cloned().collect();
+				let frame_captures = self.frame_captures.lock().split_off(0);
+				
+				let (cmd_buf, captures) = record_frame(
+					self, cmd_buf, &mut itf_renderer, &mut itf_compositor, hooks, frame_captures,
+					[win_size_x, win_size_y], resized, images, image_num
+				)?;
+				
 				let cmd_buf = cmd_buf.build().unwrap();	
 				
 				let mut future: Box<GpuFuture> = Box::new(previous_frame.join(acquire_future)) as Box<_>;
@@ -857,6 +2185,8 @@ impl Basalt {
 					Ok(ok) => ok,
 					Err(e) => match e {
 						vulkano::sync::FlushError::OutOfDate => {
+							// Frame wasn't presented, so capture the next one instead.
+							self.frame_captures.lock().extend(captures.into_iter().map(|(capture, _)| capture));
 							resized = true;
 							continue 'resize;
 						}, _ => panic!("then_signal_fence_and_flush() {:?}", e)
@@ -867,14 +2197,20 @@ impl Basalt {
 				future.cleanup_finished();
 				previous_frame = Box::new(future);
 				
+				for (capture, readback) in captures {
+					capture.complete(readback.read());
+				}
+				
 				let grab_cursor = self.mouse_capture.load(atomic::Ordering::Relaxed);
 			
 				if grab_cursor != window_grab_cursor {
-					self.surface.window().hide_cursor(grab_cursor);
-					let _ = self.surface.window().grab_cursor(grab_cursor);
+					surface.window().hide_cursor(grab_cursor);
+					let _ = surface.window().grab_cursor(grab_cursor);
 					window_grab_cursor = grab_cursor;
 				}
 				
+				frame_pacer.wait(self.frame_limit());
+				self.record_frame_time(&mut last_frame);
 				resized = false;
 				if self.wants_exit.load(atomic::Ordering::Relaxed) { break 'resize }
 			}
@@ -884,3 +2220,38 @@ impl Basalt {
 	}
 }
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn modes(immediate: bool, mailbox: bool, relaxed: bool) -> swapchain::SupportedPresentModes {
+		swapchain::SupportedPresentModes {
+			immediate,
+			mailbox,
+			fifo: true,
+			relaxed,
+			.. swapchain::SupportedPresentModes::none()
+		}
+	}
+	
+	#[test]
+	fn present_mode_supported() {
+		let all = modes(true, true, true);
+		assert_eq!(PresentMode::Fifo.select(&all), swapchain::PresentMode::Fifo);
+		assert_eq!(PresentMode::Relaxed.select(&all), swapchain::PresentMode::Relaxed);
+		assert_eq!(PresentMode::Mailbox.select(&all), swapchain::PresentMode::Mailbox);
+		assert_eq!(PresentMode::Immediate.select(&all), swapchain::PresentMode::Immediate);
+	}
+	
+	#[test]
+	fn present_mode_fallback() {
+		assert_eq!(PresentMode::Mailbox.select(&modes(true, false, false)), swapchain::PresentMode::Immediate);
+		assert_eq!(PresentMode::Immediate.select(&modes(false, true, false)), swapchain::PresentMode::Mailbox);
+		
+		let fifo_only = modes(false, false, false);
+		assert_eq!(PresentMode::Relaxed.select(&fifo_only), swapchain::PresentMode::Fifo);
+		assert_eq!(PresentMode::Mailbox.select(&fifo_only), swapchain::PresentMode::Fifo);
+		assert_eq!(PresentMode::Immediate.select(&fifo_only), swapchain::PresentMode::Fifo);
+	}
+}
@@ -0,0 +1,67 @@
+//! Diagnostics are logged through the ``log`` crate, so nothing is output unless the
+//! application installs a logger. Each subsystem logs with its own target, which can be
+//! used to filter or raise the verbosity of just that subsystem.
+//!
+//! Warnings are also kept by basalt and can be retrieved with ``Basalt::recent_warnings()``.
+
+use std::collections::VecDeque;
+use std::time::SystemTime;
+use parking_lot::Mutex;
+
+pub const INIT: &str = "basalt::init";
+pub const ATLAS: &str = "basalt::atlas";
+pub const ODB: &str = "basalt::odb";
+pub const RENDER: &str = "basalt::render";
+pub const INPUT: &str = "basalt::input";
+pub const TEXT: &str = "basalt::text";
+pub const INTERFACE: &str = "basalt::interface";
+pub const CLIPBOARD: &str = "basalt::clipboard";
+/// Messages from the validation layers.
+pub const VULKAN: &str = "basalt::vulkan";
+
+/// Amount of warnings kept, older ones are discarded.
+const MAX_WARNINGS: usize = 100;
+
+#[derive(Debug,Clone)]
+pub struct Warning {
+	/// One of the targets in this module.
+	pub target: &'static str,
+	pub message: String,
+	pub time: SystemTime,
+}
+
+pub(crate) struct Warnings {
+	recent: Mutex<VecDeque<Warning>>,
+}
+
+impl Warnings {
+	pub(crate) fn new() -> Self {
+		Warnings {
+			recent: Mutex::new(VecDeque::with_capacity(MAX_WARNINGS)),
+		}
+	}
+	
+	/// Log a warning and keep it.
+	pub(crate) fn warn(&self, target: &'static str, message: String) {
+		warn!(target: target, "{}", message);
+		let mut recent = self.recent.lock();
+		
+		if recent.len() >= MAX_WARNINGS {
+			recent.pop_front();
+		}
+		
+		recent.push_back(Warning {
+			target,
+			message,
+			time: SystemTime::now(),
+		});
+	}
+	
+	pub(crate) fn recent(&self) -> Vec<Warning> {
+		self.recent.lock().iter().cloned().collect()
+	}
+	
+	pub(crate) fn clear(&self) {
+		self.recent.lock().clear();
+	}
+}
@@ -0,0 +1,150 @@
+use std::time::{Duration,Instant};
+use std::collections::VecDeque;
+use std::thread;
+
+/// Amount of frames times kept for ``FrameStats``.
+pub(crate) const FRAME_TIMES_LEN: usize = 240;
+/// Sleeping may overshoot, so the last part of the wait is spun instead.
+const SPIN_DURATION: Duration = Duration::from_micros(1500);
+
+/// Frame time statistics in milliseconds over the most recent frames.
+#[derive(Debug,Clone,Default)]
+pub struct FrameStats {
+	pub frames: usize,
+	pub min: f32,
+	pub avg: f32,
+	pub max: f32,
+	pub p50: f32,
+	pub p95: f32,
+	pub p99: f32,
+}
+
+impl FrameStats {
+	pub(crate) fn from_times(times: &VecDeque<f32>) -> Self {
+		if times.is_empty() {
+			return FrameStats::default();
+		}
+		
+		let mut sorted: Vec<f32> = times.iter().cloned().collect();
+		sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		let percentile = |p: f32| sorted[((sorted.len() - 1) as f32 * p).round() as usize];
+		
+		FrameStats {
+			frames: sorted.len(),
+			min: sorted[0],
+			avg: sorted.iter().sum::<f32>() / sorted.len() as f32,
+			max: sorted[sorted.len() - 1],
+			p50: percentile(0.50),
+			p95: percentile(0.95),
+			p99: percentile(0.99),
+		}
+	}
+}
+
+pub(crate) struct FramePacer {
+	next: Option<Instant>,
+}
+
+impl FramePacer {
+	pub fn new() -> Self {
+		FramePacer {
+			next: None,
+		}
+	}
+	
+	/// Blocks until the next frame should start. A limit of ``None`` or zero doesn't wait.
+	pub fn wait(&mut self, limit: Option<u32>) {
+		let interval = match limit {
+			Some(limit) if limit > 0 => Duration::from_nanos(1_000_000_000 / limit as u64),
+			_ => {
+				self.next = None;
+				return;
+			}
+		};
+		
+		let target = match self.next.take() {
+			Some(some) => some,
+			None => {
+				self.next = Some(Instant::now() + interval);
+				return;
+			}
+		};
+		
+		let now = Instant::now();
+		
+		if now < target {
+			let remaining = target - now;
+			
+			if remaining > SPIN_DURATION {
+				thread::sleep(remaining - SPIN_DURATION);
+			}
+			
+			while Instant::now() < target {
+				thread::yield_now();
+			}
+		}
+		
+		// When far behind, don't try to catch up by rendering frames back to back.
+		self.next = Some(if now > target + interval {
+			Instant::now() + interval
+		} else {
+			target + interval
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn frame_stats_empty() {
+		let stats = FrameStats::from_times(&VecDeque::new());
+		assert_eq!(stats.frames, 0);
+		assert_eq!(stats.max, 0.0);
+	}
+	
+	#[test]
+	fn frame_stats_percentiles() {
+		let times: VecDeque<f32> = (1..101).rev().map(|v| v as f32).collect();
+		let stats = FrameStats::from_times(&times);
+		assert_eq!(stats.frames, 100);
+		assert_eq!(stats.min, 1.0);
+		assert_eq!(stats.avg, 50.5);
+		assert_eq!(stats.max, 100.0);
+		assert_eq!(stats.p50, 51.0);
+		assert_eq!(stats.p95, 95.0);
+		assert_eq!(stats.p99, 99.0);
+	}
+	
+	#[test]
+	fn frame_pacer_unlimited() {
+		let mut pacer = FramePacer::new();
+		pacer.wait(Some(100));
+		assert!(pacer.next.is_some());
+		pacer.wait(None);
+		assert!(pacer.next.is_none());
+		pacer.wait(Some(0));
+		assert!(pacer.next.is_none());
+	}
+	
+	#[test]
+	fn frame_pacer_limited() {
+		let mut pacer = FramePacer::new();
+		let start = Instant::now();
+		// The first frame only sets the target.
+		pacer.wait(Some(100));
+		pacer.wait(Some(100));
+		assert!(start.elapsed() >= Duration::from_millis(10));
+	}
+	
+	#[test]
+	fn frame_pacer_behind() {
+		let mut pacer = FramePacer::new();
+		pacer.wait(Some(100));
+		thread::sleep(Duration::from_millis(50));
+		pacer.wait(Some(100));
+		// The next frame is an interval from now rather than back to back.
+		assert!(pacer.next.unwrap() > Instant::now() + Duration::from_millis(5));
+	}
+}
@@ -1,10 +1,13 @@
 pub mod http;
+pub mod frame;
+pub(crate) mod readback;
 pub mod timer;
 pub mod tmp_image_access;
 
 pub use self::http::get_bytes;
 pub use self::timer::Timer;
 pub use self::tmp_image_access::TmpImageViewAccess;
+pub use self::frame::FrameStats;
 use std::sync::Arc;
 
 pub fn do_work<W: Send + 'static>(work: Vec<W>, func: Arc<Fn(W) + Send + Sync>) {
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::command_buffer::pool::standard::StandardCommandPoolBuilder;
+use vulkano::buffer::BufferUsage;
+use vulkano::buffer::cpu_access::CpuAccessibleBuffer;
+use vulkano::image::traits::ImageAccess;
+use vulkano::format::Format;
+use image::RgbaImage;
+use error::{BasaltError,RenderError};
+use Basalt;
+
+/// Copies an image into host visible memory so that it can be read once the command
+/// buffer has finished executing.
+pub(crate) struct Readback {
+	buf: Arc<CpuAccessibleBuffer<[u8]>>,
+	dims: [u32; 2],
+	bgra: bool,
+}
+
+impl Readback {
+	/// Creates the buffer an image with the same dimensions can be copied into. The image
+	/// must be a four component eight bit format.
+	pub fn new(basalt: &Arc<Basalt>, image: &Arc<ImageAccess + Send + Sync>) -> Result<Self, BasaltError> {
+		let dims = image.dimensions().width_height();
+		
+		let bgra = match image.format() {
+			Format::B8G8R8A8Srgb | Format::B8G8R8A8Unorm => true,
+			_ => false
+		};
+		
+		let buf = CpuAccessibleBuffer::from_iter(
+			basalt.device(),
+			BufferUsage {
+				transfer_destination: true,
+				.. BufferUsage::none()
+			},
+			vec![0_u8; (dims[0] * dims[1] * 4) as usize].into_iter()
+		).map_err(|e| RenderError::CreateBuffer(e))?;
+		
+		Ok(Readback {
+			buf, dims, bgra
+		})
+	}
+	
+	/// Records the copy into the command buffer. The image must not be in use by a render pass
+	/// and have the dimensions this readback was created for. On error the command buffer
+	/// is lost along with the copy.
+	pub fn record(
+		&self,
+		cmd: AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+		image: Arc<ImageAccess + Send + Sync>
+	) -> Result<AutoCommandBufferBuilder<StandardCommandPoolBuilder>, BasaltError> {
+		let dims = image.dimensions().width_height();
+		
+		if dims != self.dims {
+			return Err(RenderError::ReadbackSize { expected: self.dims, actual: dims }.into());
+		}
+		
+		cmd.copy_image_to_buffer(image, self.buf.clone()).map_err(|e| RenderError::CopyImage(e).into())
+	}
+	
+	pub fn dimensions(&self) -> [u32; 2] {
+		self.dims
+	}
+	
+	/// Only valid after the command buffer the copy was recorded into has completed. The
+	/// readback can be recorded again afterwards for another image of the same dimensions.
+	pub fn read(&self) -> Result<RgbaImage, BasaltError> {
+		let mut data = self.buf.read().map_err(|e| RenderError::ReadBuffer(e))?.to_vec();
+		
+		if self.bgra {
+			for chunk in data.chunks_exact_mut(4) {
+				chunk.swap(0, 2);
+			}
+		}
+		
+		let actual = data.len();
+		
+		RgbaImage::from_raw(self.dims[0], self.dims[1], data).ok_or(RenderError::ReadbackDataLength {
+			expected: (self.dims[0] * self.dims[1] * 4) as usize,
+			actual
+		}.into())
+	}
+}
@@ -0,0 +1,114 @@
+//! User preferences that are kept across restarts.
+//!
+//! Enabled with ``Options::prefs()`` or ``Options::prefs_path()``. The preferences are read
+//! when basalt is created and written when it shuts down or ``Basalt::save_prefs()`` is called.
+//! The default location is ``basalt/<title>.toml`` inside of ``$XDG_CONFIG_HOME``, falling
+//! back to ``~/.config``, or ``%APPDATA%`` on windows.
+
+use std::env;
+use std::fs;
+use std::path::{Path,PathBuf};
+use parking_lot::Mutex;
+use toml;
+use error::{BasaltError,PrefsError};
+use logging;
+
+#[derive(Debug,Clone,Default,PartialEq,Serialize,Deserialize)]
+pub struct Prefs {
+	/// Custom scale, excluding the dpi factor.
+	pub scale: Option<f32>,
+	pub msaa: Option<u32>,
+	/// In the same units as ``Options::window_size()``.
+	pub window_size: Option<[u32; 2]>,
+	/// In the same units as ``Basalt::set_position()``.
+	pub window_position: Option<[i32; 2]>,
+	pub fullscreen: Option<bool>,
+}
+
+impl Prefs {
+	fn load(path: &Path) -> Result<Self, BasaltError> {
+		let contents = match fs::read_to_string(path) {
+			Ok(ok) => ok,
+			Err(ref e) if e.kind() == ::std::io::ErrorKind::NotFound => return Ok(Prefs::default()),
+			Err(e) => return Err(PrefsError::Io(path.to_path_buf(), e).into())
+		};
+		
+		toml::from_str(&contents).map_err(|e| PrefsError::Parse(path.to_path_buf(), e).into())
+	}
+	
+	fn save(&self, path: &Path) -> Result<(), BasaltError> {
+		let contents = toml::to_string_pretty(self).map_err(|e| PrefsError::Serialize(e))?;
+		
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent).map_err(|e| PrefsError::Io(parent.to_path_buf(), e))?;
+		}
+		
+		fs::write(path, contents).map_err(|e| PrefsError::Io(path.to_path_buf(), e).into())
+	}
+}
+
+/// The directory basalt stores preferences in when a path isn't provided.
+pub fn config_dir() -> Option<PathBuf> {
+	#[cfg(target_os = "windows")]
+	{
+		env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("basalt"))
+	}
+	#[cfg(not(target_os = "windows"))]
+	{
+		match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+			Some(dir) => Some(PathBuf::from(dir).join("basalt")),
+			None => env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("basalt"))
+		}
+	}
+}
+
+/// File name for a window title. Anything other than ascii alphanumerics and dashes becomes
+/// an underscore.
+pub(crate) fn file_name(title: &str) -> String {
+	let name: String = title.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' }).collect();
+	
+	if name.is_empty() {
+		String::from("default.toml")
+	} else {
+		format!("{}.toml", name)
+	}
+}
+
+pub(crate) struct PrefsStore {
+	path: PathBuf,
+	prefs: Mutex<Prefs>,
+}
+
+impl PrefsStore {
+	/// Read the preferences at ``path``. If they can't be read a warning is logged and they
+	/// start out empty, they are still written to ``path`` later.
+	pub(crate) fn open(path: PathBuf) -> Self {
+		let prefs = match Prefs::load(&path) {
+			Ok(ok) => ok,
+			Err(e) => {
+				warn!(target: logging::INIT, "Failed to load preferences: {}", e);
+				Prefs::default()
+			}
+		};
+		
+		PrefsStore {
+			path,
+			prefs: Mutex::new(prefs),
+		}
+	}
+	
+	pub(crate) fn path(&self) -> &Path {
+		&self.path
+	}
+	
+	pub(crate) fn get(&self) -> Prefs {
+		self.prefs.lock().clone()
+	}
+	
+	/// Replace the preferences and write them to disk.
+	pub(crate) fn save(&self, prefs: Prefs) -> Result<(), BasaltError> {
+		let mut current = self.prefs.lock();
+		*current = prefs;
+		current.save(&self.path)
+	}
+}
@@ -0,0 +1,229 @@
+//! Timeouts, intervals and per-frame callbacks.
+//!
+//! Timeouts and intervals are called on the scheduler's own thread one at a time, so
+//! they should not block for long. Frame callbacks are called on the thread running the
+//! app loop before each frame is drawn.
+
+use std::sync::Arc;
+use std::thread::{self,JoinHandle};
+use std::time::{Duration,Instant};
+use std::collections::BTreeMap;
+use std::sync::atomic::{self,AtomicU64,AtomicBool};
+use parking_lot::{Mutex,Condvar};
+
+pub type TaskID = u64;
+pub type TimeoutFn = Arc<Fn() + Send + Sync>;
+pub type IntervalFn = Arc<Fn() -> TaskRes + Send + Sync>;
+pub type FrameFn = Arc<Fn(&FrameInfo) -> TaskRes + Send + Sync>;
+
+/// Returned by intervals and frame callbacks. ``Remove`` cancels the task.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum TaskRes {
+	Continue,
+	Remove,
+}
+
+#[derive(Debug,Clone,Copy)]
+pub struct FrameInfo {
+	/// Seconds since the previous frame. Zero for the first frame.
+	pub delta: f32,
+	/// Amount of frames before this one.
+	pub index: u64,
+}
+
+enum TaskFn {
+	Timeout(TimeoutFn),
+	Interval(Duration, IntervalFn),
+}
+
+struct Task {
+	due: Instant,
+	func: TaskFn,
+}
+
+pub struct Scheduler {
+	current_id: AtomicU64,
+	tasks: Mutex<BTreeMap<TaskID, Task>>,
+	cond: Condvar,
+	frame_fns: Mutex<BTreeMap<TaskID, FrameFn>>,
+	frame_state: Mutex<(Option<Instant>, u64)>,
+	exit: AtomicBool,
+	thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Scheduler {
+	pub(crate) fn new() -> Arc<Self> {
+		let scheduler = Arc::new(Scheduler {
+			current_id: AtomicU64::new(0),
+			tasks: Mutex::new(BTreeMap::new()),
+			cond: Condvar::new(),
+			frame_fns: Mutex::new(BTreeMap::new()),
+			frame_state: Mutex::new((None, 0)),
+			exit: AtomicBool::new(false),
+			thread: Mutex::new(None),
+		});
+		
+		let sched = scheduler.clone();
+		*scheduler.thread.lock() = Some(thread::spawn(move || sched.run()));
+		scheduler
+	}
+	
+	fn next_id(&self) -> TaskID {
+		self.current_id.fetch_add(1, atomic::Ordering::SeqCst)
+	}
+	
+	fn add_task(&self, due: Instant, func: TaskFn) -> TaskID {
+		let id = self.next_id();
+		self.tasks.lock().insert(id, Task { due, func });
+		self.cond.notify_one();
+		id
+	}
+	
+	/// Call ``func`` once after ``delay`` has elapsed.
+	pub fn set_timeout(&self, delay: Duration, func: TimeoutFn) -> TaskID {
+		self.add_task(Instant::now() + delay, TaskFn::Timeout(func))
+	}
+	
+	/// Call ``func`` every ``interval`` until it returns ``TaskRes::Remove`` or is cancelled.
+	/// The first call happens after one interval. Missed calls are skipped rather than
+	/// being called in a burst.
+	pub fn set_interval(&self, interval: Duration, func: IntervalFn) -> TaskID {
+		self.add_task(Instant::now() + interval, TaskFn::Interval(interval, func))
+	}
+	
+	/// Call ``func`` before every frame is drawn until it returns ``TaskRes::Remove`` or is
+	/// cancelled. Only called while the app loop is rendering, so when on demand rendering is
+	/// enabled it is not called while idle.
+	pub fn on_frame(&self, func: FrameFn) -> TaskID {
+		let id = self.next_id();
+		self.frame_fns.lock().insert(id, func);
+		id
+	}
+	
+	/// Cancel a timeout, interval, or frame callback. Returns false if it doesn't exist,
+	/// either because it was already cancelled or it has already finished.
+	pub fn cancel(&self, id: TaskID) -> bool {
+		self.tasks.lock().remove(&id).is_some() || self.frame_fns.lock().remove(&id).is_some()
+	}
+	
+	/// Call the frame callbacks. This is done by the app loop, applications drawing the
+	/// interface themselves should call this once before each frame.
+	pub fn run_frame(&self) {
+		let info = {
+			let mut state = self.frame_state.lock();
+			let now = Instant::now();
+			
+			let info = FrameInfo {
+				delta: match state.0 {
+					Some(last) => {
+						let elapsed = now.duration_since(last);
+						elapsed.as_secs() as f32 + (elapsed.subsec_nanos() as f32 / 1000000000.0)
+					}, None => 0.0
+				},
+				index: state.1,
+			};
+			
+			state.0 = Some(now);
+			state.1 += 1;
+			info
+		};
+		
+		let funcs: Vec<(TaskID, FrameFn)> = self.frame_fns.lock().iter().map(|(id, func)| (*id, func.clone())).collect();
+		
+		for (id, func) in funcs {
+			if func(&info) == TaskRes::Remove {
+				self.frame_fns.lock().remove(&id);
+			}
+		}
+	}
+	
+	/// Stop the scheduler's thread and drop all pending tasks. Tasks currently being called
+	/// are finished first unless this is called from one of them.
+	pub(crate) fn shutdown(&self) {
+		{
+			// Set while holding the lock so the thread can't miss the notify.
+			let _tasks = self.tasks.lock();
+			self.exit.store(true, atomic::Ordering::SeqCst);
+			self.cond.notify_one();
+		}
+		
+		if let Some(handle) = self.thread.lock().take() {
+			if handle.thread().id() != thread::current().id() {
+				let _ = handle.join();
+			}
+		}
+		
+		self.tasks.lock().clear();
+		self.frame_fns.lock().clear();
+	}
+	
+	fn run(&self) {
+		let mut tasks = self.tasks.lock();
+		
+		loop {
+			if self.exit.load(atomic::Ordering::SeqCst) {
+				return;
+			}
+			
+			let now = Instant::now();
+			let next_due = tasks.values().map(|task| task.due).min();
+			
+			match next_due {
+				Some(due) => if due > now {
+					self.cond.wait_until(&mut tasks, due);
+					continue;
+				}, None => {
+					self.cond.wait(&mut tasks);
+					continue;
+				}
+			}
+			
+			let due_ids: Vec<TaskID> = tasks.iter().filter(|(_, task)| task.due <= now).map(|(id, _)| *id).collect();
+			let mut call = Vec::with_capacity(due_ids.len());
+			
+			for id in due_ids {
+				let task = tasks.get_mut(&id).unwrap();
+				
+				let remove = match task.func {
+					TaskFn::Timeout(ref func) => {
+						call.push((id, TaskFn::Timeout(func.clone())));
+						true
+					},
+					TaskFn::Interval(interval, ref func) => {
+						call.push((id, TaskFn::Interval(interval, func.clone())));
+						task.due += interval;
+						
+						if task.due <= now {
+							task.due = now + interval;
+						}
+						
+						false
+					}
+				};
+				
+				if remove {
+					tasks.remove(&id);
+				}
+			}
+			
+			// Tasks may add or cancel other tasks, so don't hold the lock while calling.
+			drop(tasks);
+			let mut removed = Vec::new();
+			
+			for (id, func) in call {
+				match func {
+					TaskFn::Timeout(func) => func(),
+					TaskFn::Interval(_, func) => if func() == TaskRes::Remove {
+						removed.push(id);
+					}
+				}
+			}
+			
+			tasks = self.tasks.lock();
+			
+			for id in removed {
+				tasks.remove(&id);
+			}
+		}
+	}
+}
@@ -0,0 +1,16 @@
+pub mod composite_fs {
+	shader!{
+		ty: "fragment",
+		src: "
+	#version 450
+	layout(location = 0) in vec2 coords;
+	layout(location = 0) out vec4 out_color;
+	layout(set = 0, binding = 0) uniform sampler2D itf_image;
+
+	// The interface image is premultiplied, so it is blended with One, OneMinusSrcAlpha.
+	void main() {
+		out_color = texture(itf_image, coords);
+	}
+	"
+	}
+}
@@ -1,4 +1,5 @@
 include!("interface_fs.rs");
 include!("interface_vs.rs");
 include!("square_vs.rs");
+include!("composite_fs.rs");
 
@@ -0,0 +1,234 @@
+//! Helpers for golden image tests. Requires the ``test-support`` feature.
+//!
+//! A ``Golden`` renders the interface headless at a fixed scale and MSAA using the
+//! bundled font, then compares the frame against a reference png.
+//!
+//! ```no_run
+//! let golden = basalt::test_support::Golden::new(200, 100).unwrap()
+//! 	.reference_dir("tests/golden")
+//! 	.tolerance(2);
+//!
+//! let bin = golden.basalt().interface_ref().new_bin().unwrap();
+//! // Apply a style to the bin...
+//! golden.check("simple_bin").unwrap();
+//! ```
+//!
+//! Setting the environment variable ``BASALT_UPDATE_GOLDEN`` writes the rendered frame
+//! as the new reference instead of comparing.
+
+use std::sync::Arc;
+use std::path::{Path,PathBuf};
+use std::time::{Duration,Instant};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::env;
+use std::thread;
+use image::{Rgba,RgbaImage};
+use error::BasaltError;
+use Basalt;
+use Options;
+
+/// Environment variable that when set causes references to be written instead of compared.
+pub const UPDATE_ENV: &'static str = "BASALT_UPDATE_GOLDEN";
+/// MSAA used while rendering golden images.
+pub const MSAA: u32 = 4;
+/// How long to wait for the interface to finish updating before capturing anyways.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Amount of consecutive checks the interface must be idle for before it is considered settled.
+const IDLE_CHECKS: usize = 4;
+
+#[derive(Debug)]
+pub enum GoldenError {
+	Basalt(BasaltError),
+	/// No reference exists for the name. Set ``BASALT_UPDATE_GOLDEN`` to create it.
+	MissingReference(PathBuf),
+	SizeMismatch {
+		expected: [u32; 2],
+		actual: [u32; 2],
+	},
+	/// More pixels than allowed differ from the reference. The diff is written to ``diff_path``.
+	Mismatch {
+		differing: usize,
+		diff_path: PathBuf,
+	},
+	Io(PathBuf, io::Error),
+	Decode(PathBuf, ::image::ImageError),
+}
+
+impl fmt::Display for GoldenError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			GoldenError::Basalt(e) => write!(f, "{}", e),
+			GoldenError::MissingReference(path) => write!(f, "No reference image at {}, set {} to create it.", path.display(), UPDATE_ENV),
+			GoldenError::SizeMismatch { expected, actual } => write!(f, "Frame is {}x{} but the reference is {}x{}.", actual[0], actual[1], expected[0], expected[1]),
+			GoldenError::Mismatch { differing, diff_path } => write!(f, "{} pixels differ from the reference, see {}", differing, diff_path.display()),
+			GoldenError::Io(path, e) => write!(f, "Failed to access {}: {}", path.display(), e),
+			GoldenError::Decode(path, e) => write!(f, "Failed to read image {}: {}", path.display(), e),
+		}
+	}
+}
+
+impl Error for GoldenError {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		match self {
+			GoldenError::Basalt(e) => Some(e),
+			GoldenError::Io(_, e) => Some(e),
+			GoldenError::Decode(_, e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+impl From<BasaltError> for GoldenError {
+	fn from(e: BasaltError) -> Self {
+		GoldenError::Basalt(e)
+	}
+}
+
+pub struct Golden {
+	basalt: Arc<Basalt>,
+	dir: PathBuf,
+	tolerance: u8,
+	max_differing: usize,
+}
+
+impl Golden {
+	/// Create a headless basalt of the given size with the default options.
+	pub fn new(width: u32, height: u32) -> Result<Self, GoldenError> {
+		Golden::with_options(Options::default().headless(width, height))
+	}
+	
+	/// Create from custom options. Headless, scale, and on demand rendering are
+	/// overridden so that frames are deterministic.
+	pub fn with_options(options: Options) -> Result<Self, GoldenError> {
+		let size = match options.headless {
+			Some(some) => some,
+			None => options.window_size
+		};
+		
+		let basalt = Basalt::new(options.headless(size[0], size[1]).scale(1.0).on_demand(true))?;
+		basalt.interface_ref().set_msaa(MSAA)?;
+		basalt.spawn_app_loop();
+		
+		Ok(Golden {
+			basalt,
+			dir: PathBuf::from("tests/golden"),
+			tolerance: 0,
+			max_differing: 0,
+		})
+	}
+	
+	/// Directory reference images are stored in. Defaults to ``tests/golden``.
+	pub fn reference_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+		self.dir = dir.as_ref().to_path_buf();
+		self
+	}
+	
+	/// Max difference of any channel for a pixel to still be considered the same.
+	pub fn tolerance(mut self, tolerance: u8) -> Self {
+		self.tolerance = tolerance;
+		self
+	}
+	
+	/// Amount of pixels allowed to exceed the tolerance. Defaults to zero.
+	pub fn max_differing(mut self, amt: usize) -> Self {
+		self.max_differing = amt;
+		self
+	}
+	
+	pub fn basalt(&self) -> &Arc<Basalt> {
+		&self.basalt
+	}
+	
+	/// Wait for all bins, the atlas, and the buffer to finish updating then capture a frame.
+	pub fn render(&self) -> Result<RgbaImage, GoldenError> {
+		let start = Instant::now();
+		let mut idle_checks = 0;
+		
+		while idle_checks < IDLE_CHECKS && start.elapsed() < IDLE_TIMEOUT {
+			if self.basalt.interface_ref().is_idle() {
+				idle_checks += 1;
+			} else {
+				idle_checks = 0;
+			}
+			
+			thread::sleep(Duration::from_millis(5));
+		}
+		
+		Ok(self.basalt.capture_frame()?)
+	}
+	
+	/// Render and compare against ``<reference_dir>/<name>.png``. On a mismatch the frame is
+	/// written to ``<name>.actual.png`` and the differing pixels to ``<name>.diff.png``.
+	pub fn check<N: AsRef<str>>(&self, name: N) -> Result<(), GoldenError> {
+		let name = name.as_ref();
+		let actual = self.render()?;
+		let ref_path = self.dir.join(format!("{}.png", name));
+		
+		if env::var_os(UPDATE_ENV).is_some() {
+			fs::create_dir_all(&self.dir).map_err(|e| GoldenError::Io(self.dir.clone(), e))?;
+			return actual.save(&ref_path).map_err(|e| GoldenError::Io(ref_path, e));
+		}
+		
+		if !ref_path.exists() {
+			return Err(GoldenError::MissingReference(ref_path));
+		}
+		
+		let expected = ::image::open(&ref_path).map_err(|e| GoldenError::Decode(ref_path.clone(), e))?.to_rgba();
+		
+		if expected.dimensions() != actual.dimensions() {
+			let (ew, eh) = expected.dimensions();
+			let (aw, ah) = actual.dimensions();
+			
+			return Err(GoldenError::SizeMismatch {
+				expected: [ew, eh],
+				actual: [aw, ah],
+			});
+		}
+		
+		let (diff, differing) = self.diff(&expected, &actual);
+		
+		if differing <= self.max_differing {
+			return Ok(());
+		}
+		
+		let actual_path = self.dir.join(format!("{}.actual.png", name));
+		let diff_path = self.dir.join(format!("{}.diff.png", name));
+		actual.save(&actual_path).map_err(|e| GoldenError::Io(actual_path, e))?;
+		diff.save(&diff_path).map_err(|e| GoldenError::Io(diff_path.clone(), e))?;
+		
+		Err(GoldenError::Mismatch {
+			differing,
+			diff_path,
+		})
+	}
+	
+	/// Differing pixels are red, the rest are a dimmed copy of the reference.
+	fn diff(&self, expected: &RgbaImage, actual: &RgbaImage) -> (RgbaImage, usize) {
+		let mut diff = RgbaImage::new(expected.width(), expected.height());
+		let mut differing = 0;
+		
+		for (x, y, e) in expected.enumerate_pixels() {
+			let a = actual.get_pixel(x, y);
+			let max_delta = (0..4).map(|i| (e[i] as i16 - a[i] as i16).abs()).max().unwrap();
+			
+			if max_delta > self.tolerance as i16 {
+				differing += 1;
+				diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+			} else {
+				diff.put_pixel(x, y, Rgba([e[0] / 4, e[1] / 4, e[2] / 4, 255]));
+			}
+		}
+		
+		(diff, differing)
+	}
+}
+
+impl Drop for Golden {
+	fn drop(&mut self) {
+		self.basalt.exit();
+		let _ = self.basalt.wait_for_exit();
+	}
+}
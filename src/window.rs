@@ -0,0 +1,491 @@
+use std::sync::Arc;
+use std::sync::atomic::{self,AtomicBool};
+use std::thread::{self,JoinHandle};
+use std::time::Duration;
+use std::collections::BTreeMap;
+use std::path::Path;
+use parking_lot::{Mutex,Condvar};
+use vulkano::instance::PhysicalDevice;
+use vulkano::swapchain::{self,Swapchain,Surface};
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::sync::GpuFuture;
+use vulkano_win::VkSurfaceBuild;
+use winit::{self,Window,WindowEvent,WindowId};
+use interface::interface::Interface;
+use interface::hook::InputEvent as ItfInputEvent;
+use interface::render::{ItfRenderer,ItfCompositor};
+use input::{Qwery,MouseButton};
+use error::{BasaltError,InitError,RenderError};
+use misc;
+use image;
+use {Basalt,FrameCapture,RenderHookID,RenderHookFn,RenderStage};
+use logging;
+
+/// Options for windows created with ``Basalt::create_window()``.
+#[derive(Debug,Clone)]
+pub struct WindowOptions {
+	title: String,
+	window_size: [u32; 2],
+	scale: f32,
+}
+
+impl Default for WindowOptions {
+	fn default() -> Self {
+		WindowOptions {
+			title: "vk-basalt".to_string(),
+			window_size: [800, 600],
+			scale: 1.0,
+		}
+	}
+}
+
+impl WindowOptions {
+	pub fn title<T: AsRef<str>>(mut self, title: T) -> Self {
+		self.title = String::from(title.as_ref());
+		self
+	}
+	
+	/// Size of the window. This is logical unless ``ignore_dpi`` is set on basalt's options.
+	pub fn window_size(mut self, width: u32, height: u32) -> Self {
+		self.window_size = [width, height];
+		self
+	}
+	
+	pub fn scale(mut self, to: f32) -> Self {
+		self.scale = to;
+		self
+	}
+}
+
+pub(crate) struct WindowRequest {
+	options: WindowOptions,
+	result: Arc<Mutex<Option<Result<Arc<BasaltWindow>, BasaltError>>>>,
+	condvar: Arc<Condvar>,
+}
+
+impl WindowRequest {
+	pub fn new(options: WindowOptions) -> Self {
+		WindowRequest {
+			options,
+			result: Arc::new(Mutex::new(None)),
+			condvar: Arc::new(Condvar::new()),
+		}
+	}
+	
+	/// Blocks until the event loop thread has handled the request.
+	pub fn wait(
+		result: Arc<Mutex<Option<Result<Arc<BasaltWindow>, BasaltError>>>>,
+		condvar: Arc<Condvar>
+	) -> Result<Arc<BasaltWindow>, BasaltError> {
+		let mut result = result.lock();
+		
+		while result.is_none() {
+			condvar.wait(&mut result);
+		}
+		
+		result.take().unwrap()
+	}
+	
+	pub fn handles(&self) -> (Arc<Mutex<Option<Result<Arc<BasaltWindow>, BasaltError>>>>, Arc<Condvar>) {
+		(self.result.clone(), self.condvar.clone())
+	}
+	
+	/// Must be called on the thread owning the event loop.
+	pub fn complete(self, basalt: &Arc<Basalt>, events_loop: &winit::EventsLoop) {
+		let result = BasaltWindow::new(basalt, events_loop, self.options);
+		
+		if let Ok(window) = result.as_ref() {
+			basalt.windows.lock().push(window.clone());
+		}
+		
+		*self.result.lock() = Some(result);
+		self.condvar.notify_one();
+	}
+}
+
+/// An additional window. Each window has its own swapchain and ``Interface``, while the
+/// device, atlas and text cache are shared with basalt.
+pub struct BasaltWindow {
+	basalt: Arc<Basalt>,
+	id: WindowId,
+	/// Taken when the window is closed so that it can be destroyed.
+	surface: Mutex<Option<Arc<Surface<Window>>>>,
+	interface: Arc<Interface>,
+	custom_scale: Mutex<f32>,
+	window_size: Mutex<[u32; 2]>,
+	force_resize: AtomicBool,
+	closed: AtomicBool,
+	mouse_inside: AtomicBool,
+	mouse_position: Mutex<Option<[f32; 2]>>,
+	loop_thread: Mutex<Option<JoinHandle<Result<(), BasaltError>>>>,
+	render_hooks: Mutex<BTreeMap<RenderHookID, (RenderStage, RenderHookFn)>>,
+	frame_captures: Mutex<Vec<FrameCapture>>,
+}
+
+impl BasaltWindow {
+	fn new(basalt: &Arc<Basalt>, events_loop: &winit::EventsLoop, options: WindowOptions) -> Result<Arc<Self>, BasaltError> {
+		let instance = match basalt.surface_ref() {
+			Some(surface) => surface.instance().clone(),
+			None => return Err(InitError::NoEventLoop.into())
+		};
+		
+		let surface = match winit::WindowBuilder::new()
+			.with_dimensions((options.window_size[0], options.window_size[1]).into())
+			.with_title(options.title.clone())
+			.with_transparency(basalt.options.transparent)
+			.build_vk_surface(events_loop, instance)
+		{
+			Ok(ok) => ok,
+			Err(e) => return Err(InitError::CreateWindow(e).into())
+		};
+		
+		if basalt.options.ignore_dpi {
+			surface.window().set_inner_size(winit::dpi::PhysicalSize::new(
+				options.window_size[0] as f64,
+				options.window_size[1] as f64
+			).to_logical(surface.window().get_hidpi_factor()));
+		}
+		
+		let interface = Interface::with_text(basalt.clone(), basalt.interface_ref().text_ref().clone());
+		
+		let window = Arc::new(BasaltWindow {
+			basalt: basalt.clone(),
+			id: surface.window().id(),
+			surface: Mutex::new(Some(surface)),
+			interface,
+			custom_scale: Mutex::new(options.scale),
+			window_size: Mutex::new(options.window_size),
+			force_resize: AtomicBool::new(false),
+			closed: AtomicBool::new(false),
+			mouse_inside: AtomicBool::new(false),
+			mouse_position: Mutex::new(None),
+			loop_thread: Mutex::new(None),
+			render_hooks: Mutex::new(BTreeMap::new()),
+			frame_captures: Mutex::new(Vec::new()),
+		});
+		
+		window.apply_scale();
+		let window_cp = window.clone();
+		
+		*window.loop_thread.lock() = Some(thread::spawn(move || {
+			let result = window_cp.render_loop();
+			window_cp.destroy();
+			result
+		}));
+		
+		Ok(window)
+	}
+	
+	pub fn id(&self) -> WindowId {
+		self.id
+	}
+	
+	/// ``None`` once the window has been closed.
+	pub fn surface(&self) -> Option<Arc<Surface<Window>>> {
+		self.surface.lock().clone()
+	} pub fn interface(&self) -> Arc<Interface> {
+		self.interface.clone()
+	} pub fn interface_ref(&self) -> &Arc<Interface> {
+		&self.interface
+	} pub fn window_size(&self) -> [u32; 2] {
+		*self.window_size.lock()
+	} pub fn hidpi_factor(&self) -> f64 {
+		self.surface().map(|surface| surface.window().get_hidpi_factor()).unwrap_or(1.0)
+	} pub fn is_closed(&self) -> bool {
+		self.closed.load(atomic::Ordering::Relaxed)
+	}
+	
+	pub fn current_scale(&self) -> f32 {
+		*self.custom_scale.lock()
+	}
+	
+	pub fn set_scale(&self, to: f32) {
+		*self.custom_scale.lock() = to;
+		self.apply_scale();
+	}
+	
+	fn apply_scale(&self) {
+		let custom_scale = *self.custom_scale.lock();
+		
+		if self.basalt.options.ignore_dpi {
+			self.interface.set_scale(custom_scale);
+		} else {
+			self.interface.set_scale(custom_scale * self.hidpi_factor() as f32);
+		}
+	}
+	
+	/// Closes the window and stops rendering to it. Bins created from this window's
+	/// interface will no longer be displayed. The window is destroyed once its render
+	/// loop has exited.
+	pub fn close(&self) {
+		if !self.closed.swap(true, atomic::Ordering::SeqCst) {
+			self.basalt.remove_window(self.id);
+			// Wake the render loop in case it is waiting for a redraw.
+			self.basalt.request_redraw();
+		}
+	}
+	
+	/// Called after the render loop has exited.
+	fn destroy(&self) {
+		self.closed.store(true, atomic::Ordering::SeqCst);
+		self.basalt.remove_window(self.id);
+		self.render_hooks.lock().clear();
+		self.interface.capture_renderer.lock().take();
+		
+		for capture in self.frame_captures.lock().split_off(0) {
+			capture.complete(Err(RenderError::WindowClosed.into()));
+		}
+		
+		if let Some(surface) = self.surface.lock().take() {
+			self.basalt.destroy_surface(surface);
+		}
+	}
+	
+	/// Same as ``Basalt::add_render_hook()`` but for this window.
+	pub fn add_render_hook(&self, stage: RenderStage, func: RenderHookFn) -> RenderHookID {
+		let id = self.basalt.next_render_hook_id();
+		self.render_hooks.lock().insert(id, (stage, func));
+		self.basalt.request_redraw();
+		id
+	}
+	
+	pub fn remove_render_hook(&self, id: RenderHookID) {
+		self.render_hooks.lock().remove(&id);
+		self.basalt.request_redraw();
+	}
+	
+	/// Same as ``Basalt::capture_frame()`` but for this window.
+	pub fn capture_frame(&self) -> Result<image::RgbaImage, BasaltError> {
+		let capture = FrameCapture::new();
+		
+		{
+			// Checked while locked so the capture can't be added after destroy() is done with it.
+			let mut frame_captures = self.frame_captures.lock();
+			
+			if self.is_closed() {
+				return Err(RenderError::WindowClosed.into());
+			}
+			
+			frame_captures.push(capture.clone());
+		}
+		
+		self.basalt.request_redraw();
+		capture.wait()
+	}
+	
+	pub fn capture_frame_png<P: AsRef<Path>>(&self, path: P) -> Result<(), BasaltError> {
+		let path = path.as_ref();
+		
+		self.capture_frame()?.save(path).map_err(|e| {
+			RenderError::SaveImage(path.to_path_buf(), e).into()
+		})
+	}
+	
+	/// Block until the render loop of this window has exited.
+	pub fn wait_for_close(&self) -> Result<(), BasaltError> {
+		match self.loop_thread.lock().take() {
+			Some(handle) => match handle.join() {
+				Ok(ok) => ok,
+				Err(_) => Err(::error::RenderError::LoopThreadPanicked.into())
+			}, None => Ok(())
+		}
+	}
+	
+	pub(crate) fn mouse_inside(&self) -> bool {
+		self.mouse_inside.load(atomic::Ordering::Relaxed)
+	}
+	
+	/// Scroll events come from device events, so they are routed by the event loop to the
+	/// window the cursor is inside of.
+	pub(crate) fn send_scroll(&self, amt: f32) {
+		self.interface.hook_manager.send_event(ItfInputEvent::Scroll(amt));
+	}
+	
+	pub(crate) fn send_scroll_x(&self, amt: f32) {
+		self.interface.hook_manager.send_event(ItfInputEvent::ScrollX(amt));
+	}
+	
+	/// Events for this window are sent here instead of to ``Input``.
+	pub(crate) fn handle_event(&self, event: WindowEvent) {
+		let hook_manager = &self.interface.hook_manager;
+		
+		match event {
+			WindowEvent::CloseRequested => self.close(),
+			
+			WindowEvent::CursorMoved { position, .. } => {
+				let winit::dpi::PhysicalPosition { x, y } = position.to_physical(self.hidpi_factor());
+				let (x, y) = (x as f32, y as f32);
+				let mut mouse_position = self.mouse_position.lock();
+				
+				// The hook manager only checks what the mouse is over after it has moved.
+				let (dx, dy) = match mouse_position.take() {
+					Some([lx, ly]) => (x - lx, y - ly),
+					None => (0.0, 0.0)
+				};
+				
+				*mouse_position = Some([x, y]);
+				hook_manager.send_event(ItfInputEvent::MousePosition(x, y));
+				hook_manager.send_event(ItfInputEvent::MouseDelta(dx, dy));
+			},
+			
+			WindowEvent::KeyboardInput { input, .. } => {
+				hook_manager.send_event(match input.state {
+					winit::ElementState::Pressed => ItfInputEvent::KeyPress(Qwery::from(input.scancode)),
+					winit::ElementState::Released => ItfInputEvent::KeyRelease(Qwery::from(input.scancode)),
+				});
+			},
+			
+			WindowEvent::MouseInput { state, button, .. } => {
+				let button = match button {
+					winit::MouseButton::Left => MouseButton::Left,
+					winit::MouseButton::Right => MouseButton::Right,
+					winit::MouseButton::Middle => MouseButton::Middle,
+					_ => return
+				};
+				
+				hook_manager.send_event(match state {
+					winit::ElementState::Pressed => ItfInputEvent::MousePress(button),
+					winit::ElementState::Released => ItfInputEvent::MouseRelease(button),
+				});
+			},
+			
+			// Elsewhere the raw device axes are forwarded by the main event loop.
+			#[cfg(target_os = "windows")]
+			WindowEvent::MouseWheel { delta, .. } => {
+				let (x, y) = match delta {
+					winit::MouseScrollDelta::LineDelta(x, y) => (x, -y),
+					winit::MouseScrollDelta::PixelDelta(data) => (data.x as f32, data.y as f32)
+				};
+				
+				if y != 0.0 {
+					self.send_scroll(y);
+				}
+				
+				if x != 0.0 {
+					self.send_scroll_x(x);
+				}
+			},
+			
+			WindowEvent::CursorEntered { .. } => self.mouse_inside.store(true, atomic::Ordering::Relaxed),
+			WindowEvent::CursorLeft { .. } => self.mouse_inside.store(false, atomic::Ordering::Relaxed),
+			WindowEvent::Resized { .. } => self.force_resize.store(true, atomic::Ordering::Relaxed),
+			WindowEvent::HiDpiFactorChanged(_) => self.apply_scale(),
+			_ => ()
+		}
+	}
+	
+	fn render_loop(self: &Arc<Self>) -> Result<(), BasaltError> {
+		let surface = match self.surface() {
+			Some(some) => some,
+			None => return Ok(())
+		};
+		
+		let physical = PhysicalDevice::from_index(surface.instance(), self.basalt.physical_device_index()).unwrap();
+		
+		let swap_caps = match surface.capabilities(physical) {
+			Ok(ok) => ok,
+			Err(e) => return Err(InitError::SurfaceCapabilities(e).into())
+		};
+		
+		let swapchain_format = ::swapchain_format(&swap_caps)?;
+		let composite_alpha = ::composite_alpha(&swap_caps, self.basalt.options.transparent)?;
+		let mut itf_renderer = ItfRenderer::with_interface(self.basalt.clone(), self.interface.clone());
+		let mut itf_compositor = ItfCompositor::new(self.basalt.clone());
+		let mut frame_pacer = misc::frame::FramePacer::new();
+		let mut redraw_seen = 0;
+		let mut swapchain_ = None;
+		let mut resized = true;
+		
+		'resize: while !self.is_closed() && !self.basalt.wants_exit() {
+			let [x, y] = ::surface_extent(&surface, physical)?;
+			*self.window_size.lock() = [x, y];
+			let present_mode = self.basalt.present_mode().select(&swap_caps.present_modes);
+			let old_swapchain = swapchain_.as_ref().map(|v: &(Arc<Swapchain<_>>, _)| v.0.clone());
+			
+			swapchain_ = Some(match Swapchain::new(
+				self.basalt.device(), surface.clone(),
+				swap_caps.min_image_count, swapchain_format,
+				[x, y], 1, swap_caps.supported_usage_flags,
+				self.basalt.graphics_queue_ref(), swapchain::SurfaceTransform::Identity,
+				composite_alpha, present_mode,
+				true, old_swapchain.as_ref()
+			) {
+				Ok(ok) => ok,
+				Err(e) => {
+					debug!(target: logging::RENDER, "swapchain recreation error: {:?}", e);
+					thread::sleep(Duration::from_millis(10));
+					continue;
+				}
+			});
+			
+			let (swapchain, images) = (&swapchain_.as_ref().unwrap().0, &swapchain_.as_ref().unwrap().1);
+			let mut previous_frame = Box::new(vulkano::sync::now(self.basalt.device())) as Box<GpuFuture>;
+			
+			loop {
+				if !resized {
+					self.basalt.wait_for_redraw(&mut redraw_seen);
+				}
+				
+				if self.is_closed() || self.basalt.wants_exit() {
+					break 'resize;
+				}
+				
+				if self.force_resize.swap(false, atomic::Ordering::Relaxed) {
+					resized = true;
+					continue 'resize;
+				}
+				
+				self.basalt.scheduler_ref().run_frame();
+				
+				let (image_num, acquire_future) = match swapchain::acquire_next_image(swapchain.clone(), Some(Duration::new(1, 0))) {
+					Ok(ok) => ok,
+					Err(e) => {
+						debug!(target: logging::RENDER, "swapchain::acquire_next_image() Err: {:?}", e);
+						resized = true;
+						continue 'resize;
+					}
+				};
+				
+				let cmd_buf = AutoCommandBufferBuilder::primary_one_time_submit(self.basalt.device(), self.basalt.graphics_queue_ref().family()).unwrap();
+				let hooks = self.render_hooks.lock().values().cloned().collect();
+				let frame_captures = self.frame_captures.lock().split_off(0);
+				
+				let (cmd_buf, captures) = ::record_frame(
+					&self.basalt, cmd_buf, &mut itf_renderer, &mut itf_compositor, hooks, frame_captures,
+					[x, y], resized, images, image_num
+				)?;
+				
+				let cmd_buf = cmd_buf.build().unwrap();
+				
+				let mut future = match previous_frame.join(acquire_future)
+					.then_execute(self.basalt.graphics_queue(), cmd_buf).unwrap()
+					.then_swapchain_present(self.basalt.graphics_queue(), swapchain.clone(), image_num)
+					.then_signal_fence_and_flush()
+				{
+					Ok(ok) => ok,
+					Err(e) => match e {
+						vulkano::sync::FlushError::OutOfDate => {
+							// Frame wasn't presented, so capture the next one instead.
+							self.frame_captures.lock().extend(captures.into_iter().map(|(capture, _)| capture));
+							resized = true;
+							continue 'resize;
+						}, _ => panic!("then_signal_fence_and_flush() {:?}", e)
+					}
+				};
+				
+				future.wait(None).unwrap();
+				future.cleanup_finished();
+				previous_frame = Box::new(future);
+				
+				for (capture, readback) in captures {
+					capture.complete(readback.read());
+				}
+				
+				frame_pacer.wait(self.basalt.frame_limit());
+				resized = false;
+			}
+		}
+		
+		Ok(())
+	}
+}